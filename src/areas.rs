@@ -0,0 +1,325 @@
+//! Source locations of the definitions in `areas.wotw`
+//!
+//! seedgen's logic parser doesn't expose the spans of what it parses, so this module finds the lines of anchors and their connections
+//! to report `SourceRange`s. Everything else, including requirements, is taken from seedgen's parsed logic.
+//! Once seedgen provides spans, this module should be replaced by them
+
+use std::collections::HashMap;
+
+use crate::map::{SourceLocation, SourceRange};
+
+/// A non-empty line of `areas.wotw` syntax along with the lines indented below it
+pub(crate) struct LineTree<'a> {
+    /// Zero-based index of this line
+    pub line: usize,
+    /// Column of the first character of `content`, in UTF-16 code units
+    pub start: usize,
+    /// The line content without surrounding whitespace and comments
+    pub content: &'a str,
+    /// Lines indented further than this line which directly follow it
+    pub children: Vec<LineTree<'a>>,
+    indent: usize,
+}
+impl LineTree<'_> {
+    /// The range from the start of this line to the end of its last nested line
+    pub fn range(&self) -> SourceRange {
+        let last = self.last();
+        SourceRange {
+            start: SourceLocation {
+                line: self.line,
+                char: self.start,
+            },
+            end: SourceLocation {
+                line: last.line,
+                char: last.start + last.content.encode_utf16().count(),
+            },
+        }
+    }
+    fn last(&self) -> &Self {
+        self.children.last().map_or(self, LineTree::last)
+    }
+    /// The ranges of every line nested inside this line, each including its own nested lines
+    fn nested_ranges(&self) -> Vec<SourceRange> {
+        self.children
+            .iter()
+            .flat_map(|child| {
+                let mut ranges = vec![child.range()];
+                ranges.extend(child.nested_ranges());
+                ranges
+            })
+            .collect()
+    }
+    /// The range of any text following the first `:` on this line
+    fn inline_range(&self) -> Option<SourceRange> {
+        let (before, after) = self.content.split_once(':')?;
+        let trimmed = after.trim_start();
+        if trimmed.is_empty() {
+            return None;
+        }
+        let start = self.start
+            + before.encode_utf16().count()
+            + 1
+            + after[..after.len() - trimmed.len()].encode_utf16().count();
+        Some(SourceRange {
+            start: SourceLocation {
+                line: self.line,
+                char: start,
+            },
+            end: SourceLocation {
+                line: self.line,
+                char: start + trimmed.encode_utf16().count(),
+            },
+        })
+    }
+}
+
+/// Splits `areas` into `LineTree`s based on indentation, ignoring empty lines and comments
+pub(crate) fn line_trees(areas: &str) -> Vec<LineTree<'_>> {
+    let mut roots: Vec<LineTree> = vec![];
+
+    for (line, text) in areas.lines().enumerate() {
+        let text = text.find('#').map_or(text, |comment| &text[..comment]);
+        let content = text.trim();
+        if content.is_empty() {
+            continue;
+        }
+        let indentation = &text[..text.len() - text.trim_start().len()];
+        let tree = LineTree {
+            line,
+            start: indentation.encode_utf16().count(),
+            content,
+            children: vec![],
+            indent: indentation.chars().count(),
+        };
+
+        let mut siblings = &mut roots;
+        while siblings
+            .last()
+            .is_some_and(|parent| parent.indent < tree.indent)
+        {
+            siblings = &mut siblings.last_mut().unwrap().children;
+        }
+        siblings.push(tree);
+    }
+
+    roots
+}
+
+/// An `anchor` definition in `areas.wotw`
+pub(crate) struct AnchorDefinition {
+    /// The range of the whole definition, including all nested lines
+    pub range: SourceRange,
+    /// Definitions of the connections leading away from this anchor
+    pub connections: Vec<ConnectionDefinition>,
+}
+
+/// A `conn`, `pickup`, `quest`, `state` or `refill` line inside an anchor definition
+pub(crate) struct ConnectionDefinition {
+    /// The keyword starting the line, e.g. `conn`
+    pub keyword: &'static str,
+    /// The name following the keyword
+    ///
+    /// For `refill` lines, this is the kind of refill without its amount, e.g. `Energy` for `refill Energy=2`
+    pub target: String,
+    /// The range of the whole definition, including the requirement
+    pub range: SourceRange,
+    /// The ranges of the requirement written after the `:` and every requirement line nested below the definition
+    pub requirement_ranges: Vec<SourceRange>,
+}
+
+/// The keywords starting lines which define a `ConnectionDefinition`
+const CONNECTION_KEYWORDS: [&str; 5] = ["conn", "pickup", "quest", "state", "refill"];
+
+/// Parses the anchor definitions in `areas`, by anchor name
+pub(crate) fn anchors(areas: &str) -> HashMap<String, AnchorDefinition> {
+    line_trees(areas)
+        .iter()
+        .filter_map(|tree| {
+            let name = definition_name(tree.content, "anchor")?;
+            let connections = tree
+                .children
+                .iter()
+                .filter_map(|child| {
+                    CONNECTION_KEYWORDS.into_iter().find_map(|keyword| {
+                        let mut target = definition_name(child.content, keyword)?;
+                        if keyword == "refill" {
+                            target = target.split('=').next().unwrap_or(target);
+                        }
+                        let mut requirement_ranges = child.nested_ranges();
+                        requirement_ranges.extend(child.inline_range());
+                        Some(ConnectionDefinition {
+                            keyword,
+                            target: target.to_owned(),
+                            range: child.range(),
+                            requirement_ranges,
+                        })
+                    })
+                })
+                .collect();
+            let anchor = AnchorDefinition {
+                range: tree.range(),
+                connections,
+            };
+            Some((name.to_owned(), anchor))
+        })
+        .collect()
+}
+
+/// Returns the name in a line like `<keyword> <name> at x, y:` or `<keyword> <name>: requirement`
+fn definition_name<'a>(content: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = content.strip_prefix(keyword)?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let name = rest
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .next()?;
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREAS: &str = "\
+# Marsh
+anchor MarshSpawn.Main at -799, -4310:  # spawn
+  refill Checkpoint
+  conn MarshSpawn.MainX: free
+  conn MarshSpawn.Main2:
+    # nested comment
+    moki, Dash
+    gorlek:
+      Bash
+  pickup MarshSpawn.Ore: Glide
+
+anchor MarshSpawn.MainX at 0, 0:
+  conn MarshSpawn.Main: free
+  refill Energy=2: Bash
+  refill Energy=2
+";
+
+    fn range(start: (usize, usize), end: (usize, usize)) -> (usize, usize, usize, usize) {
+        (start.0, start.1, end.0, end.1)
+    }
+    fn tuple(range: SourceRange) -> (usize, usize, usize, usize) {
+        (
+            range.start.line,
+            range.start.char,
+            range.end.line,
+            range.end.char,
+        )
+    }
+
+    #[test]
+    fn line_trees_nest_by_indentation() {
+        let trees = line_trees(AREAS);
+        assert_eq!(trees.len(), 2);
+        let anchor = &trees[0];
+        assert_eq!(anchor.children.len(), 4);
+        let conn = &anchor.children[2];
+        assert_eq!(conn.content, "conn MarshSpawn.Main2:");
+        assert_eq!(conn.children.len(), 2);
+        assert_eq!(conn.children[1].content, "gorlek:");
+        assert_eq!(conn.children[1].children[0].content, "Bash");
+        assert_eq!(tuple(anchor.range()), range((1, 0), (9, 30)));
+    }
+
+    #[test]
+    fn comments_are_ignored() {
+        let trees = line_trees(AREAS);
+        assert_eq!(trees[0].line, 1);
+        assert_eq!(trees[0].content, "anchor MarshSpawn.Main at -799, -4310:");
+        let nested = &trees[0].children[2].children[0];
+        assert_eq!(nested.line, 6);
+        assert_eq!(nested.content, "moki, Dash");
+    }
+
+    #[test]
+    fn names_sharing_a_prefix() {
+        let anchors = anchors(AREAS);
+        assert_eq!(anchors.len(), 2);
+        assert_eq!(
+            tuple(anchors["MarshSpawn.MainX"].range),
+            range((11, 0), (14, 17))
+        );
+        let targets = anchors["MarshSpawn.Main"]
+            .connections
+            .iter()
+            .map(|connection| connection.target.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            targets,
+            [
+                "Checkpoint",
+                "MarshSpawn.MainX",
+                "MarshSpawn.Main2",
+                "MarshSpawn.Ore"
+            ]
+        );
+
+        assert_eq!(
+            definition_name("connect MarshSpawn.Main: free", "conn"),
+            None
+        );
+        assert_eq!(definition_name("anchor A at 0, 0:", "anchor"), Some("A"));
+    }
+
+    #[test]
+    fn refill_amounts() {
+        let anchors = anchors(AREAS);
+        let refills = anchors["MarshSpawn.MainX"]
+            .connections
+            .iter()
+            .filter(|connection| connection.keyword == "refill")
+            .map(|connection| (connection.target.as_str(), tuple(connection.range)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            refills,
+            [
+                ("Energy", range((13, 2), (13, 23))),
+                ("Energy", range((14, 2), (14, 17))),
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_requirements() {
+        let anchors = anchors(AREAS);
+        let connections = &anchors["MarshSpawn.Main"].connections;
+        let ranges = |index: usize| {
+            connections[index]
+                .requirement_ranges
+                .iter()
+                .copied()
+                .map(tuple)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranges(0), []);
+        assert_eq!(ranges(1), [range((3, 25), (3, 29))]);
+        assert_eq!(ranges(3), [range((9, 25), (9, 30))]);
+        assert_eq!(tuple(connections[3].range), range((9, 2), (9, 30)));
+    }
+
+    #[test]
+    fn nested_requirements() {
+        let anchors = anchors(AREAS);
+        let connection = &anchors["MarshSpawn.Main"].connections[2];
+        let ranges = connection
+            .requirement_ranges
+            .iter()
+            .copied()
+            .map(tuple)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            [
+                range((6, 4), (6, 14)),
+                range((7, 4), (8, 10)),
+                range((8, 6), (8, 10)),
+            ]
+        );
+        assert_eq!(tuple(connection.range), range((4, 2), (8, 10)));
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use wasm_bindgen::prelude::*;
+
+use crate::map::{ConnectionType, Graph};
+
+/// Radius of the circles drawn for `Node`s in `Graph.to_svg`, using in-game units
+const SVG_NODE_RADIUS: f32 = 3.;
+/// Empty space around the `Node`s in `Graph.to_svg`, using in-game units
+const SVG_MARGIN: f32 = 20.;
+
+#[wasm_bindgen]
+impl Graph {
+    #[wasm_bindgen]
+    /// Serializes this `Graph` into the GraphViz DOT language
+    ///
+    /// `Node`s are pinned to their in-game position through the `pos` attribute, which layout engines like `neato -n` respect
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph logic {\n".to_string();
+        for node in &self.nodes {
+            let _ = writeln!(
+                dot,
+                "  {} [pos=\"{},{}!\"];",
+                dot_id(&node.name),
+                node.position.x,
+                node.position.y
+            );
+        }
+        for connection in &self.connections {
+            let mut attributes = vec![];
+            if !connection.unidirectional {
+                attributes.push("dir=both");
+            }
+            if !matches!(
+                connection.kind,
+                ConnectionType::Branch | ConnectionType::Teleporter | ConnectionType::Door
+            ) {
+                attributes.push("style=dashed");
+            }
+            let attributes = if attributes.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attributes.join(", "))
+            };
+            let _ = writeln!(
+                dot,
+                "  {} -> {}{attributes};",
+                dot_id(&connection.start),
+                dot_id(&connection.end)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+    #[wasm_bindgen]
+    /// Serializes this `Graph` into a GeoJSON `FeatureCollection`, using in-game coordinates
+    ///
+    /// `Node`s become `Point` features with a `name` property,
+    /// `Connection`s become `LineString` features with `start`, `end`, `unidirectional` and `type` properties
+    pub fn to_geojson(&self) -> String {
+        let nodes = self.nodes.iter().map(|node| {
+            format!(
+                r#"{{"type":"Feature","geometry":{{"type":"Point","coordinates":[{},{}]}},"properties":{{"name":{}}}}}"#,
+                node.position.x,
+                node.position.y,
+                json_string(&node.name)
+            )
+        });
+        let positions = self.node_positions();
+        let connections = self.connections.iter().filter_map(|connection| {
+            let start = positions.get(connection.start.as_str())?;
+            let end = positions.get(connection.end.as_str())?;
+            let kind = match connection.kind {
+                ConnectionType::Branch => "Branch",
+                ConnectionType::Leaf => "Leaf",
+                ConnectionType::Teleporter => "Teleporter",
+                ConnectionType::Door => "Door",
+            };
+            Some(format!(
+                r#"{{"type":"Feature","geometry":{{"type":"LineString","coordinates":[[{},{}],[{},{}]]}},"properties":{{"start":{},"end":{},"unidirectional":{},"type":"{kind}"}}}}"#,
+                start.0,
+                start.1,
+                end.0,
+                end.1,
+                json_string(&connection.start),
+                json_string(&connection.end),
+                connection.unidirectional
+            ))
+        });
+        let features = nodes.chain(connections).collect::<Vec<_>>();
+        format!(
+            r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+            features.join(",")
+        )
+    }
+    #[wasm_bindgen]
+    /// Renders this `Graph` into a standalone SVG document
+    ///
+    /// One SVG unit equals one in-game unit, the y axis is flipped so the image matches the in-game map.
+    /// Every `Node` has a `<title>` with its name, which most viewers show on hover
+    pub fn to_svg(&self) -> String {
+        let (min_x, min_y, max_x, max_y) = self
+            .nodes
+            .iter()
+            .map(|node| (node.position.x, -node.position.y))
+            .fold(None, |bounds, (x, y)| {
+                Some(match bounds {
+                    None => (x, y, x, y),
+                    Some((min_x, min_y, max_x, max_y)) => (
+                        f32::min(min_x, x),
+                        f32::min(min_y, y),
+                        f32::max(max_x, x),
+                        f32::max(max_y, y),
+                    ),
+                })
+            })
+            .unwrap_or_default();
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            min_x - SVG_MARGIN,
+            min_y - SVG_MARGIN,
+            max_x - min_x + 2. * SVG_MARGIN,
+            max_y - min_y + 2. * SVG_MARGIN
+        );
+        svg.push('\n');
+        let positions = self.node_positions();
+        for connection in &self.connections {
+            let (Some(start), Some(end)) = (
+                positions.get(connection.start.as_str()),
+                positions.get(connection.end.as_str()),
+            ) else {
+                continue;
+            };
+            let color = match connection.kind {
+                ConnectionType::Branch => "#888",
+                ConnectionType::Leaf => "#4a4",
+                ConnectionType::Teleporter => "#a4c",
+                ConnectionType::Door => "#c84",
+            };
+            let _ = writeln!(
+                svg,
+                r#"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{color}" stroke-width="1"/>"#,
+                start.0, -start.1, end.0, -end.1
+            );
+        }
+        for node in &self.nodes {
+            let _ = writeln!(
+                svg,
+                r##"  <circle cx="{}" cy="{}" r="{SVG_NODE_RADIUS}" fill="#36c"><title>{}</title></circle>"##,
+                node.position.x,
+                -node.position.y,
+                xml_text(&node.name)
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+impl Graph {
+    /// The positions of all `Node`s by name
+    fn node_positions(&self) -> HashMap<&str, (f32, f32)> {
+        self.nodes
+            .iter()
+            .map(|node| (node.name.as_str(), (node.position.x, node.position.y)))
+            .collect()
+    }
+}
+
+/// Quotes `id` for use as a DOT identifier
+fn dot_id(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+/// Quotes `string` for use as a JSON string
+fn json_string(string: &str) -> String {
+    let mut json = String::with_capacity(string.len() + 2);
+    json.push('"');
+    for char in string.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            char if char.is_control() => {
+                let _ = write!(json, "\\u{:04x}", char as u32);
+            }
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
+/// Escapes `text` for use inside an XML element
+fn xml_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
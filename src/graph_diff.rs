@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use wasm_bindgen::prelude::*;
+
+use wasm_bindgen_helper_macros::*;
+
+use crate::map::{self, graph, Graph, Vector2};
+use crate::requirement::Requirement;
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// How a `Node` differs between two versions of the logic
+    pub enum NodeChangeKind {
+        /// The `Node` only exists in the new logic
+        Added,
+        /// The `Node` only exists in the old logic
+        Removed,
+        /// The `Node` has a different position in the new logic, or only has a map position in one of them
+        Moved,
+    }
+}
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// How a connection differs between two versions of the logic
+    pub enum ConnectionChangeKind {
+        /// The connection only exists in the new logic
+        Added,
+        /// The connection only exists in the old logic
+        Removed,
+        /// The new requirement is met whenever the old requirement was met
+        Loosened,
+        /// The old requirement is met whenever the new requirement is met
+        Tightened,
+        /// The requirement changed in a way that is neither strictly looser nor tighter
+        Changed,
+    }
+}
+
+/// The differences between two versions of the logic, see `diff_graphs`
+#[wasm_bindgen]
+pub struct GraphDiff {
+    nodes: Vec<NodeChange>,
+    connections: Vec<ConnectionChange>,
+}
+#[wasm_bindgen]
+impl GraphDiff {
+    /// `Node`s that were added, removed or moved
+    #[wasm_bindgen(getter)]
+    pub fn nodes(&self) -> NodeChangeArray {
+        __NodeChangeList::from(self.nodes.clone()).into_js_array()
+    }
+    /// Connections that were added, removed or had their requirement changed
+    #[wasm_bindgen(getter)]
+    pub fn connections(&self) -> ConnectionChangeArray {
+        __ConnectionChangeList::from(self.connections.clone()).into_js_array()
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __NodeChangeList {
+        inner: IntoIter<NodeChange>,
+    }
+}
+
+/// A `Node` that differs between two versions of the logic
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct NodeChange {
+    /// The name of the `Node`
+    #[wasm_bindgen(getter_with_clone)]
+    pub name: String,
+    /// The position in the old logic, `undefined` if the `Node` was added or had no map position
+    pub old_position: Option<Vector2>,
+    /// The position in the new logic, `undefined` if the `Node` was removed or has no map position
+    pub new_position: Option<Vector2>,
+    kind: NodeChangeKind,
+}
+#[wasm_bindgen]
+impl NodeChange {
+    /// The `NodeChangeKind` of this change
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> NodeChangeKindEnum {
+        self.kind.into_js_enum()
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __ConnectionChangeList {
+        inner: IntoIter<ConnectionChange>,
+    }
+}
+
+/// A connection that differs between two versions of the logic
+///
+/// Unlike the `Connection`s of a `Graph`, each direction is compared separately
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct ConnectionChange {
+    /// Name of the anchor this connection starts at
+    #[wasm_bindgen(getter_with_clone)]
+    pub start: String,
+    /// Name of the `Node` this connection ends at, or the kind of refill like `Checkpoint` for `refill` lines
+    #[wasm_bindgen(getter_with_clone)]
+    pub end: String,
+    /// The requirement in the old logic, `undefined` if the connection was added
+    #[wasm_bindgen(getter_with_clone)]
+    pub old_requirement: Option<Requirement>,
+    /// The requirement in the new logic, `undefined` if the connection was removed
+    #[wasm_bindgen(getter_with_clone)]
+    pub new_requirement: Option<Requirement>,
+    kind: ConnectionChangeKind,
+}
+#[wasm_bindgen]
+impl ConnectionChange {
+    /// The `ConnectionChangeKind` of this change
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> ConnectionChangeKindEnum {
+        self.kind.into_js_enum()
+    }
+}
+
+/// Compares two versions of `areas.wotw` and returns the differences relevant to the map
+///
+/// Both versions are parsed with the same `locations`, see `graph`.
+/// Requirements are compared after seedgen expanded their macros and regions.
+/// Requirement changes are reported as loosened or tightened if possible, requirements that are written differently but are equivalent are not reported
+///
+/// @throws {string} Throws if either version fails to parse
+#[wasm_bindgen]
+pub fn diff_graphs(
+    old_areas: &str,
+    new_areas: &str,
+    locations: &str,
+) -> Result<GraphDiff, JsValue> {
+    let old = graph(old_areas, locations)?;
+    let new = graph(new_areas, locations)?;
+
+    Ok(GraphDiff {
+        nodes: diff_nodes(&old, &new),
+        connections: diff_connections(&old, &new),
+    })
+}
+
+fn diff_nodes(old: &Graph, new: &Graph) -> Vec<NodeChange> {
+    // Nodes without a map position are compared as well, gaining or losing a position counts as moving
+    let positions = |graph: &Graph| {
+        graph
+            .logic
+            .nodes
+            .iter()
+            .map(|node| {
+                let position = node.map_position().cloned().map(Vector2::from);
+                (node.identifier().to_owned(), position)
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    let (old, new) = (positions(old), positions(new));
+    let names = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (old_position, new_position) = (old.get(name).copied(), new.get(name).copied());
+            let kind = match (old_position, new_position) {
+                (None, Some(_)) => NodeChangeKind::Added,
+                (Some(_), None) => NodeChangeKind::Removed,
+                (Some(Some(old)), Some(Some(new))) if old.x == new.x && old.y == new.y => {
+                    return None
+                }
+                (Some(None), Some(None)) => return None,
+                _ => NodeChangeKind::Moved,
+            };
+            let (old_position, new_position) = (old_position.flatten(), new_position.flatten());
+            Some(NodeChange {
+                name: name.clone(),
+                old_position,
+                new_position,
+                kind,
+            })
+        })
+        .collect()
+}
+
+fn diff_connections(old: &Graph, new: &Graph) -> Vec<ConnectionChange> {
+    let requirements = |graph: &Graph| {
+        let nodes = &graph.logic.nodes;
+        map::anchors(nodes)
+            .flat_map(|anchor| {
+                let connections = anchor
+                    .connections
+                    .iter()
+                    .filter(|connection| !connection.implicitly_generated)
+                    .map(|connection| {
+                        (
+                            nodes[connection.to].identifier().to_owned(),
+                            &connection.requirement,
+                        )
+                    });
+                let refills = anchor.refills.iter().map(|refill| {
+                    (
+                        map::refill_name(&refill.value).to_owned(),
+                        &refill.requirement,
+                    )
+                });
+                connections.chain(refills).map(|(target, requirement)| {
+                    (
+                        (anchor.identifier.clone(), target),
+                        Requirement::from_seedgen(requirement, nodes),
+                    )
+                })
+            })
+            .fold(BTreeMap::new(), |mut requirements, (pair, requirement)| {
+                // Several lines with the same anchor and target, like two refills of the same kind, are compared in order
+                let occurrence = requirements
+                    .range((pair.0.clone(), pair.1.clone(), 0)..)
+                    .take_while(|((start, end, _), _)| (start, end) == (&pair.0, &pair.1))
+                    .count();
+                requirements.insert((pair.0, pair.1, occurrence), requirement);
+                requirements
+            })
+    };
+    let (old, new) = (requirements(old), requirements(new));
+    let pairs = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+
+    pairs
+        .into_iter()
+        .filter_map(|pair| {
+            let old_requirement = old.get(pair).cloned();
+            let new_requirement = new.get(pair).cloned();
+            let kind = match (&old_requirement, &new_requirement) {
+                (None, _) => ConnectionChangeKind::Added,
+                (_, None) => ConnectionChangeKind::Removed,
+                (Some(old), Some(new)) => match (old.implies(new), new.implies(old)) {
+                    (true, true) => return None,
+                    (true, false) => ConnectionChangeKind::Loosened,
+                    (false, true) => ConnectionChangeKind::Tightened,
+                    (false, false) => ConnectionChangeKind::Changed,
+                },
+            };
+            let (start, end, _) = pair.clone();
+            Some(ConnectionChange {
+                start,
+                end,
+                old_requirement,
+                new_requirement,
+                kind,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+/// A non-empty line of header syntax with comments removed
+pub(crate) struct HeaderLine<'a> {
+    /// Zero-based index of this line in the header
+    pub line: usize,
+    /// The line content without surrounding whitespace and comments
+    pub content: &'a str,
+}
+impl<'a> HeaderLine<'a> {
+    /// Splits a `!!command arguments` line into the command name and its (trimmed) arguments
+    pub fn command(&self) -> Option<(&'a str, &'a str)> {
+        let command = self.content.strip_prefix("!!")?;
+        let (name, arguments) = command.split_once(' ').unwrap_or((command, ""));
+        Some((name, arguments.trim()))
+    }
+}
+
+/// Iterates over all lines of the header that contain anything besides whitespace and comments
+pub(crate) fn lines(header: &str) -> impl Iterator<Item = HeaderLine<'_>> {
+    header.lines().enumerate().filter_map(|(line, text)| {
+        let text = text.find("//").map_or(text, |comment| &text[..comment]);
+        let content = text.trim();
+
+        (!content.is_empty()).then_some(HeaderLine { line, content })
+    })
+}
+
+/// Returns the names of all headers included through `!!include`
+pub(crate) fn includes(header: &str) -> Vec<String> {
+    lines(header)
+        .filter_map(|line| match line.command()? {
+            ("include", name) if !name.is_empty() => Some(name.to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Annotations only this package understands, seedgen's parser doesn't know them
+const EXTENSION_ANNOTATIONS: [&str; 4] = ["#requires", "#group", "#order", "#advanced"];
+
+/// Returns `true` if `content` is one of the `annotations`, e.g. `#group Logic` for `#group`
+pub(crate) fn is_annotation(content: &str, annotations: &[&str]) -> bool {
+    content
+        .split_whitespace()
+        .next()
+        .is_some_and(|name| annotations.contains(&name))
+}
+
+/// Blanks out the `EXTENSION_ANNOTATIONS` so the header can be handed to seedgen's parser, the line numbers stay the same
+pub(crate) fn seedgen_syntax(header: &str) -> String {
+    header
+        .lines()
+        .map(|text| {
+            let code = text.find("//").map_or(text, |comment| &text[..comment]);
+            if is_annotation(code, &EXTENSION_ANNOTATIONS) {
+                ""
+            } else {
+                text
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the arguments of every `#requires` annotation
+pub(crate) fn requirements(header: &str) -> Vec<String> {
+    lines(header)
+        .filter_map(|line| line.content.strip_prefix("#requires "))
+        .map(|requirement| requirement.trim().to_owned())
+        .collect()
+}
+
+/// Display information for a parameter, declared using annotations before its `!!parameter` line
+#[derive(Default)]
+pub(crate) struct ParameterMetadata {
+    /// Title of the section started by the last `#group` annotation
+    pub group: Option<String>,
+    /// Value of an `#order` annotation directly before the parameter
+    pub order: Option<i32>,
+    /// Whether an `#advanced` annotation is directly before the parameter
+    pub advanced: bool,
+}
+
+/// Returns the argument of `content` if it is the `annotation`, e.g. `Logic` for `#group Logic` and `#group`
+fn annotation_argument<'a>(content: &'a str, annotation: &str) -> Option<&'a str> {
+    is_annotation(content, &[annotation]).then(|| content[annotation.len()..].trim())
+}
+
+/// Returns the `ParameterMetadata` of every declared parameter by identifier
+///
+/// `#order` and `#advanced` only apply if nothing but other annotations is between them and the `!!parameter` line
+pub(crate) fn parameter_metadata(header: &str) -> HashMap<String, ParameterMetadata> {
+    let mut metadata = HashMap::new();
+    let mut group = None;
+    let mut pending = ParameterMetadata::default();
+
+    for line in lines(header) {
+        if let Some(title) = annotation_argument(line.content, "#group") {
+            group = (!title.is_empty()).then(|| title.to_owned());
+        } else if let Some(order) = annotation_argument(line.content, "#order") {
+            pending.order = order.parse().ok();
+        } else if is_annotation(line.content, &["#advanced"]) {
+            pending.advanced = true;
+        } else if line.content.starts_with('#') {
+            // Other annotations don't separate the metadata from its parameter
+        } else {
+            if let Some(("parameter", arguments)) = line.command() {
+                if let Some(identifier) = arguments.split_whitespace().next() {
+                    pending.group = group.clone();
+                    metadata.insert(identifier.to_owned(), std::mem::take(&mut pending));
+                }
+            }
+            pending = ParameterMetadata::default();
+        }
+    }
+
+    metadata
+}
+
+/// Returns the `#order` annotations whose value is not an integer
+pub(crate) fn invalid_orders(header: &str) -> Vec<HeaderLine<'_>> {
+    lines(header)
+        .filter(|line| {
+            annotation_argument(line.content, "#order")
+                .is_some_and(|order| order.parse::<i32>().is_err())
+        })
+        .collect()
+}
+
+/// Location of an identifier in a header
+pub(crate) struct IdentifierSpan {
+    /// Zero-based index of the line
+    pub line: usize,
+    /// Column of the first character, in UTF-16 code units
+    pub start: usize,
+    /// Column after the last character, in UTF-16 code units
+    pub end: usize,
+}
+
+/// Returns the declaration and every `$PARAM` reference of the parameter `identifier`, ignoring comments
+pub(crate) fn parameter_spans(header: &str, identifier: &str) -> Vec<IdentifierSpan> {
+    let mut spans = vec![];
+    for (line, text) in header.lines().enumerate() {
+        let code = text.find("//").map_or(text, |comment| &text[..comment]);
+        let mut push_span = |index: usize| {
+            let start = code[..index].encode_utf16().count();
+            let end = start + identifier.encode_utf16().count();
+            spans.push(IdentifierSpan { line, start, end });
+        };
+
+        let content = code.trim_start();
+        if let Some(arguments) = content.strip_prefix("!!parameter ") {
+            let declared = arguments.trim_start();
+            if declared.split_whitespace().next() == Some(identifier) {
+                push_span(code.len() - declared.len());
+            }
+        }
+
+        let reference = format!("$PARAM({identifier})");
+        for (index, _) in code.match_indices(&reference) {
+            push_span(index + 7);
+        }
+    }
+    spans
+}
+
+/// An uber state referenced by a header
+pub(crate) struct UberStateSpan {
+    /// The uber group id as written
+    pub group: String,
+    /// The uber state id as written, without any value condition
+    pub id: String,
+    /// `true` if the header changes the uber state, `false` if it only checks its value
+    pub write: bool,
+    /// Location of `group|id`
+    pub span: IdentifierSpan,
+}
+
+/// Commands followed by an item which may reference uber states
+const ITEM_COMMANDS: [&str; 2] = ["add", "remove"];
+/// Ids of the command items which stop a pickup chain based on an uber state: `StopEqual`, `StopGreater` and `StopLess`
+const STOP_COMMANDS: [&str; 3] = ["4", "5", "6"];
+/// Ids of the command items which grant a nested item based on an uber state: `IfEqual`, `IfGreater` and `IfLess`
+const IF_COMMANDS: [&str; 3] = ["17", "18", "19"];
+/// Ids of the command items which count up an uber state over time: `StartTimer` and `StopTimer`
+const TIMER_COMMANDS: [&str; 2] = ["9", "10"];
+
+/// Returns every uber state referenced by the header, see `uber_states::uber_state_usage` for the recognized references
+pub(crate) fn uber_state_spans(header: &str) -> Vec<UberStateSpan> {
+    let mut spans = vec![];
+    for (line, text) in header.lines().enumerate() {
+        let code = text.find("//").map_or(text, |comment| &text[..comment]);
+        let mut push_span = |index: usize, group: &str, id: &str, write: bool| {
+            if group.parse::<i32>().is_err() || id.parse::<i32>().is_err() {
+                return;
+            }
+            let start = code[..index].encode_utf16().count();
+            let end = start + group.encode_utf16().count() + 1 + id.encode_utf16().count();
+            spans.push(UberStateSpan {
+                group: group.to_owned(),
+                id: id.to_owned(),
+                write,
+                span: IdentifierSpan { line, start, end },
+            });
+        };
+
+        for (index, _) in code.match_indices("$(") {
+            let reference = &code[index + 2..];
+            let Some((group, id)) = reference
+                .split_once(')')
+                .and_then(|(reference, _)| reference.split_once('|'))
+            else {
+                continue;
+            };
+            push_span(index + 2, group, id, false);
+        }
+
+        let content = code.trim_start();
+        let content_index = code.len() - content.len();
+        if let Some(command) = content.strip_prefix("!!") {
+            let Some((name, item)) = command.split_once(' ') else {
+                continue;
+            };
+            if ITEM_COMMANDS.contains(&name) {
+                let item = item.trim_start();
+                item_spans(item, code.len() - item.len(), &mut push_span);
+            }
+            continue;
+        }
+
+        let (content, content_index) = match content.strip_prefix('!') {
+            Some(content) => (content, content_index + 1),
+            None => (content, content_index),
+        };
+        let mut parts = content.splitn(3, '|');
+        let (Some(group), Some(id)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        // The trigger may carry a condition like `6|1=2` or `6|1>2`
+        let id = id.split(['=', '>', '<']).next().unwrap_or_default().trim();
+        if group.parse::<i32>().is_err() || id.parse::<i32>().is_err() {
+            continue;
+        }
+        push_span(content_index, group, id, false);
+        if let Some(item) = parts.next() {
+            item_spans(item, code.len() - item.len(), &mut push_span);
+        }
+    }
+    spans
+}
+/// Passes the uber states referenced by `item` to `push_span`, `offset` is the index of `item` in its line
+fn item_spans(item: &str, offset: usize, push_span: &mut dyn FnMut(usize, &str, &str, bool)) {
+    let mut parts = vec![];
+    let mut index = offset;
+    for part in item.split('|') {
+        let trimmed = part.trim();
+        parts.push((index + part.len() - part.trim_start().len(), trimmed));
+        index += part.len() + 1;
+    }
+    let names = parts.iter().map(|(_, part)| *part).collect::<Vec<_>>();
+
+    match names.as_slice() {
+        ["8", group, id, ..] => push_span(parts[1].0, group, id, true),
+        ["4", command, group, id, ..] if STOP_COMMANDS.contains(command) => {
+            push_span(parts[2].0, group, id, false)
+        }
+        ["4", command, group, id, ..] if TIMER_COMMANDS.contains(command) => {
+            push_span(parts[2].0, group, id, true)
+        }
+        ["4", command, group, id, _, ..] if IF_COMMANDS.contains(command) => {
+            push_span(parts[2].0, group, id, false);
+            if let Some((nested, _)) = parts.get(5) {
+                item_spans(&item[nested - offset..], *nested, push_span);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_applies_to_the_next_parameter() {
+        let header = "\
+#group Logic
+#order 2
+#hide
+!!parameter a bool:true
+#order 1
+#advanced
+!!add 1|1
+!!parameter b int:0
+#grouping
+#order abc
+!!parameter c int:0
+";
+        let metadata = parameter_metadata(header);
+        assert_eq!(metadata["a"].group.as_deref(), Some("Logic"));
+        assert_eq!(metadata["a"].order, Some(2));
+        assert_eq!(metadata["b"].order, None);
+        assert!(!metadata["b"].advanced);
+        assert_eq!(metadata["c"].group.as_deref(), Some("Logic"));
+        assert_eq!(metadata["c"].order, None);
+
+        let invalid = invalid_orders(header)
+            .iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>();
+        assert_eq!(invalid, [9]);
+    }
+
+    #[test]
+    fn parameter_declaration_and_references() {
+        let header = "\
+!!parameter speed float:1.5 // $PARAM(speed) in a comment
+3|0|8|9|0|int|$PARAM(speed)
+!!parameter speedy int:1
+🌊 $PARAM(speed) $PARAM(speedy)
+";
+        let spans = parameter_spans(header, "speed")
+            .into_iter()
+            .map(|span| (span.line, span.start, span.end))
+            .collect::<Vec<_>>();
+        // The emoji takes two UTF-16 code units
+        assert_eq!(spans, [(0, 12, 17), (1, 21, 26), (3, 10, 15)]);
+    }
+
+    #[test]
+    fn uber_state_references() {
+        let header = "\
+3|0|4|17|1|2|3|8|7|8|bool|true
+  !!add 8|6|5|int|+1
+$(12|34) $(x|1) // 8|1|1
+";
+        let spans = uber_state_spans(header)
+            .into_iter()
+            .map(|span| {
+                let IdentifierSpan { line, start, end } = span.span;
+                (line, span.group, span.id, span.write, start, end)
+            })
+            .collect::<Vec<_>>();
+        let span = |line, group: &str, id: &str, write, start, end| {
+            (line, group.to_owned(), id.to_owned(), write, start, end)
+        };
+        assert_eq!(
+            spans,
+            [
+                span(0, "3", "0", false, 0, 3),
+                span(0, "1", "2", false, 9, 12),
+                // The item granted by `IfEqual` is nested behind its value
+                span(0, "7", "8", true, 17, 20),
+                span(1, "6", "5", true, 10, 13),
+                span(2, "12", "34", false, 2, 7),
+            ]
+        );
+    }
+}
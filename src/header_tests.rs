@@ -0,0 +1,255 @@
+use wasm_bindgen::prelude::*;
+
+use wotw_seedgen::settings::UniverseSettings as SeedgenUniverseSettings;
+
+use wasm_bindgen_helper_macros::*;
+
+use crate::files::JsFileAccess;
+use crate::headers::{compile_preview, unmet_requirements, HeaderPreview};
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __HeaderTestResultList {
+        inner: IntoIter<HeaderTestResult>,
+    }
+}
+
+/// The outcome of one test case, see `run_header_tests`
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct HeaderTestResult {
+    /// The name given to the test case
+    #[wasm_bindgen(getter_with_clone)]
+    pub name: String,
+    /// `true` if every expectation of the test case was met
+    pub passed: bool,
+    /// A description of every expectation that was not met
+    #[wasm_bindgen(getter_with_clone)]
+    pub failures: Vec<String>,
+}
+
+/// Compiles a given header syntax for every world of every test case in `test_spec` and checks the expectations of each case against every world
+///
+/// `test_spec` consists of lines in the following syntax, lines starting with `//` are ignored:
+/// - `test <name>` starts a new test case, all following lines belong to it
+/// - `parameter <identifier>=<value>` overrides a parameter default
+/// - `settings <json>` sets the `UniverseSettings` to compile with, using the default settings if omitted.
+///   The header is compiled with the settings of each world, failures name the world if there are several
+/// - `expect compiles` expects the header to compile for every world and all of its `#requires` annotations to be met
+/// - `expect fails` expects the header to fail compiling or have unmet `#requires` annotations
+/// - `expect adds <item>` expects the item to be added to the item pool
+/// - `expect removes <item>` expects the item to be removed from the item pool
+/// - `expect places <location> [<item>]` expects a pickup on the location, e.g. `expect places 3|0 0|100`
+/// - `expect sets <uber state> [<value>]` expects the uber state to be set on spawn, e.g. `expect sets 6|1 true`
+/// - `expect flag <flag>` expects the flag to be set
+///
+/// Every test case implicitly expects the header to compile unless it contains `expect fails`
+///
+/// @throws {string} if `test_spec` fails to parse
+#[wasm_bindgen]
+pub fn run_header_tests(
+    header: &str,
+    test_spec: &str,
+    file_access: &JsFileAccess,
+) -> Result<HeaderTestResultArray, String> {
+    let results = parse_test_spec(test_spec)?
+        .into_iter()
+        .map(|test_case| test_case.run(header, file_access))
+        .collect::<Vec<_>>();
+
+    Ok(__HeaderTestResultList::from(results).into_js_array())
+}
+
+struct TestCase {
+    name: String,
+    settings: SeedgenUniverseSettings,
+    parameter_values: Vec<String>,
+    expectations: Vec<Expectation>,
+}
+impl TestCase {
+    fn run(self, header: &str, file_access: &JsFileAccess) -> HeaderTestResult {
+        let mut failures = unmet_requirements(header, &self.settings)
+            .into_iter()
+            .map(|unmet| {
+                format!(
+                    "requirement \"{}\" is not met: {}",
+                    unmet.requirement, unmet.reason
+                )
+            })
+            .collect::<Vec<_>>();
+        let worlds = self.settings.world_settings.len();
+        let previews = if worlds == 0 {
+            failures.push("the settings contain no worlds".to_string());
+            vec![]
+        } else {
+            self.settings
+                .world_settings
+                .iter()
+                .enumerate()
+                .filter_map(|(index, world_settings)| {
+                    let world = if worlds > 1 {
+                        format!("world {index} ")
+                    } else {
+                        String::new()
+                    };
+                    match compile_preview(
+                        header,
+                        self.parameter_values.clone(),
+                        world_settings,
+                        file_access,
+                    ) {
+                        Ok(preview) => Some((world, preview)),
+                        Err(err) => {
+                            failures.push(format!("{world}failed to compile: {err}"));
+                            None
+                        }
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let compiled = failures.is_empty();
+
+        let failures = if self.expectations.contains(&Expectation::Fails) {
+            if compiled {
+                vec!["expected the header to fail compiling".to_string()]
+            } else {
+                vec![]
+            }
+        } else if compiled {
+            previews
+                .iter()
+                .flat_map(|(world, preview)| {
+                    self.expectations
+                        .iter()
+                        .filter_map(move |expectation| expectation.check(preview))
+                        .map(move |failure| format!("{world}{failure}"))
+                })
+                .collect()
+        } else {
+            failures
+        };
+
+        HeaderTestResult {
+            name: self.name,
+            passed: failures.is_empty(),
+            failures,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Expectation {
+    Compiles,
+    Fails,
+    Adds(String),
+    Removes(String),
+    Places(String, Option<String>),
+    Sets(String, Option<String>),
+    Flag(String),
+}
+impl Expectation {
+    fn parse(expectation: &str) -> Option<Self> {
+        let (kind, arguments) = expectation
+            .split_once(' ')
+            .map_or((expectation, ""), |(kind, arguments)| {
+                (kind, arguments.trim())
+            });
+        let (first, second) = arguments
+            .split_once(' ')
+            .map_or((arguments, None), |(first, second)| {
+                (first, Some(second.trim().to_owned()))
+            });
+
+        let expectation = match (kind, arguments.is_empty()) {
+            ("compiles", true) => Expectation::Compiles,
+            ("fails", true) => Expectation::Fails,
+            ("adds", false) => Expectation::Adds(arguments.to_owned()),
+            ("removes", false) => Expectation::Removes(arguments.to_owned()),
+            ("places", false) => Expectation::Places(first.to_owned(), second),
+            ("sets", false) => Expectation::Sets(first.to_owned(), second),
+            ("flag", false) => Expectation::Flag(arguments.to_owned()),
+            _ => return None,
+        };
+        Some(expectation)
+    }
+    /// Returns a description of the failure, or `None` if the expectation is met
+    fn check(&self, preview: &HeaderPreview) -> Option<String> {
+        match self {
+            Expectation::Compiles | Expectation::Fails => None,
+            Expectation::Adds(item) => (!preview.pool_additions.contains(item))
+                .then(|| format!("expected {item} to be added to the item pool")),
+            Expectation::Removes(item) => (!preview.pool_removals.contains(item))
+                .then(|| format!("expected {item} to be removed from the item pool")),
+            Expectation::Places(location, item) => {
+                let placed = preview.pickups.iter().any(|pickup| {
+                    pickup.location == *location
+                        && item.as_ref().is_none_or(|item| pickup.item == *item)
+                });
+                (!placed).then(|| match item {
+                    None => format!("expected a pickup on {location}"),
+                    Some(item) => format!("expected {item} to be placed on {location}"),
+                })
+            }
+            Expectation::Sets(uber_state, value) => {
+                let set = preview.spawn_states.iter().any(|spawn_state| {
+                    spawn_state.uber_state == *uber_state
+                        && value
+                            .as_ref()
+                            .is_none_or(|value| spawn_state.value == *value)
+                });
+                (!set).then(|| match value {
+                    None => format!("expected {uber_state} to be set on spawn"),
+                    Some(value) => format!("expected {uber_state} to be set to {value} on spawn"),
+                })
+            }
+            Expectation::Flag(flag) => (!preview.flags.contains(flag))
+                .then(|| format!("expected the flag {flag} to be set")),
+        }
+    }
+}
+
+fn parse_test_spec(test_spec: &str) -> Result<Vec<TestCase>, String> {
+    let mut test_cases: Vec<TestCase> = vec![];
+
+    for (index, line) in test_spec.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        let error = |message: &str| format!("line {}: {message}", index + 1);
+
+        let (directive, arguments) = line
+            .split_once(' ')
+            .map_or((line, ""), |(directive, arguments)| {
+                (directive, arguments.trim())
+            });
+        if directive == "test" {
+            test_cases.push(TestCase {
+                name: arguments.to_owned(),
+                settings: SeedgenUniverseSettings::default(),
+                parameter_values: vec![],
+                expectations: vec![],
+            });
+            continue;
+        }
+
+        let test_case = test_cases
+            .last_mut()
+            .ok_or_else(|| error("expected a test line before anything else"))?;
+        match directive {
+            "parameter" => test_case.parameter_values.push(arguments.to_owned()),
+            "settings" => {
+                test_case.settings = SeedgenUniverseSettings::parse(arguments)
+                    .map_err(|err| error(&format!("invalid settings: {err}")))?
+            }
+            "expect" => test_case.expectations.push(
+                Expectation::parse(arguments)
+                    .ok_or_else(|| error(&format!("unknown expectation \"{arguments}\"")))?,
+            ),
+            _ => return Err(error(&format!("unknown directive \"{directive}\""))),
+        }
+    }
+
+    Ok(test_cases)
+}
@@ -1,13 +1,26 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
 use wasm_bindgen::prelude::*;
 
+use wotw_seedgen::files::FileAccess;
 use wotw_seedgen::header::Annotation as SeedgenAnnotation;
 use wotw_seedgen::header::Header;
 use wotw_seedgen::header::HeaderDocumentation as SeedgenHeaderDocumentation;
 use wotw_seedgen::header::ParameterDefault as SeedgenParameterDefault;
 use wotw_seedgen::header::ParameterInfo as SeedgenParameterInfo;
+use wotw_seedgen::item::Item;
+use wotw_seedgen::settings::Goal;
+use wotw_seedgen::settings::UniverseSettings as SeedgenUniverseSettings;
+use wotw_seedgen::settings::WorldSettings as SeedgenWorldSettings;
+use wotw_seedgen::util::UberIdentifier;
 
 use wasm_bindgen_helper_macros::*;
 
+use crate::files::JsFileAccess;
+use crate::header_syntax;
+use crate::settings::{UniverseSettings, WorldSettings};
+
 /// Meta information contained in a header's annotations
 #[wasm_bindgen]
 #[derive(Default)]
@@ -15,8 +28,26 @@ pub struct Annotations {
     /// Hide this header from the user, it is only to be used internally through includes
     pub hide: bool,
     /// Put this header into a category with other, similar headers
+    ///
+    /// Categories may be nested by separating them with `/`, e.g. `QoL/Movement`
     #[wasm_bindgen(getter_with_clone)]
     pub category: Option<String>,
+    /// Requirements on the settings this header should be used with, see `check_header_compatibility`
+    #[wasm_bindgen(getter_with_clone)]
+    pub requires: Vec<String>,
+}
+#[wasm_bindgen]
+impl Annotations {
+    /// The segments of the `category`, e.g. `["QoL", "Movement"]` for `QoL/Movement`
+    ///
+    /// Empty if there is no `category`
+    #[wasm_bindgen(getter)]
+    pub fn category_path(&self) -> Vec<String> {
+        self.category
+            .as_deref()
+            .map(category_path)
+            .unwrap_or_default()
+    }
 }
 impl From<Vec<SeedgenAnnotation>> for Annotations {
     fn from(annotation_vec: Vec<SeedgenAnnotation>) -> Annotations {
@@ -33,10 +64,215 @@ impl From<Vec<SeedgenAnnotation>> for Annotations {
 
 /// Returns the annotations of a given header syntax
 ///
-/// This will only parse the minimum amount required to know the annotations
+/// This will only parse the minimum amount required to know the annotations.
+/// `#requires` is only understood by this package and never passed on to seedgen
 #[wasm_bindgen]
 pub fn parse_annotations(header: &str) -> Result<Annotations, String> {
-    Header::parse_annotations(header).map(Annotations::from)
+    let mut annotations =
+        Header::parse_annotations(&header_syntax::seedgen_syntax(header)).map(Annotations::from)?;
+    annotations.requires = header_syntax::requirements(header);
+    Ok(annotations)
+}
+
+fn category_path(category: &str) -> Vec<String> {
+    category
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __CategoryList {
+        inner: IntoIter<Category>,
+    }
+}
+
+/// A node in the category tree, see `category_tree`
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Category {
+    /// The last segment of this category's path
+    ///
+    /// Empty for the root of the tree
+    #[wasm_bindgen(getter_with_clone)]
+    pub name: String,
+    /// Names of the headers placed directly into this category
+    #[wasm_bindgen(getter_with_clone)]
+    pub headers: Vec<String>,
+    /// Number of headers in this category and all of its subcategories
+    pub count: usize,
+    subcategories: Vec<Category>,
+}
+#[wasm_bindgen]
+impl Category {
+    /// Nested categories, in the order they were first encountered
+    #[wasm_bindgen(getter)]
+    pub fn subcategories(&self) -> CategoryArray {
+        __CategoryList::from(self.subcategories.clone()).into_js_array()
+    }
+}
+impl Category {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            headers: vec![],
+            count: 0,
+            subcategories: vec![],
+        }
+    }
+    fn insert(&mut self, path: &[String], header: String) {
+        self.count += 1;
+        match path.split_first() {
+            None => self.headers.push(header),
+            Some((name, rest)) => {
+                let index = match self
+                    .subcategories
+                    .iter()
+                    .position(|subcategory| subcategory.name == *name)
+                {
+                    Some(index) => index,
+                    None => {
+                        self.subcategories.push(Category::new(name.clone()));
+                        self.subcategories.len() - 1
+                    }
+                };
+                self.subcategories[index].insert(rest, header);
+            }
+        }
+    }
+}
+
+/// Returns a tree of the categories used by the given headers
+///
+/// The headers are read using the provided `file_access`, headers without a category are placed into the root.
+/// Hidden headers are left out
+///
+/// @throws {string} if a header cannot be found or its annotations fail to parse
+#[wasm_bindgen]
+pub fn category_tree(names: Vec<String>, file_access: &JsFileAccess) -> Result<Category, String> {
+    let mut root = Category::new(String::new());
+    for name in names {
+        let header = file_access
+            .read_header(&name)
+            .map_err(|err| format!("failed to read header \"{name}\": {err}"))?;
+        let annotations = parse_annotations(&header)?;
+        if !annotations.hide {
+            root.insert(&annotations.category_path(), name);
+        }
+    }
+    Ok(root)
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __UnmetRequirementList {
+        inner: IntoIter<UnmetRequirement>,
+    }
+}
+
+/// A `#requires` annotation that is not met by the settings
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct UnmetRequirement {
+    /// The requirement as written in the annotation, e.g. `multiworld`
+    #[wasm_bindgen(getter_with_clone)]
+    pub requirement: String,
+    /// Why the requirement is not met
+    #[wasm_bindgen(getter_with_clone)]
+    pub reason: String,
+}
+
+/// Returns the `#requires` annotations of a given header syntax that are not met by `universe_settings`
+///
+/// Supported requirements are:
+/// - `#requires multiworld`: the settings have to contain more than one world
+/// - `#requires goal <goal>`: every world has to use the goal, one of `trees`, `wisps`, `quests` or `relics`, e.g. `#requires goal trees`
+/// - `#requires seedgen <comparison> <version>`: the version of seedgen has to match, e.g. `#requires seedgen >= 0.7`
+///
+/// Unknown requirements are reported as unmet
+#[wasm_bindgen]
+pub fn check_header_compatibility(
+    header: &str,
+    universe_settings: &UniverseSettings,
+) -> UnmetRequirementArray {
+    __UnmetRequirementList::from(unmet_requirements(header, &universe_settings.0)).into_js_array()
+}
+pub(crate) fn unmet_requirements(
+    header: &str,
+    settings: &SeedgenUniverseSettings,
+) -> Vec<UnmetRequirement> {
+    header_syntax::requirements(header)
+        .into_iter()
+        .filter_map(|requirement| {
+            let reason = unmet_reason(&requirement, settings)?;
+            Some(UnmetRequirement {
+                requirement,
+                reason,
+            })
+        })
+        .collect()
+}
+/// Returns why `requirement` is not met, or `None` if it is met
+fn unmet_reason(requirement: &str, settings: &SeedgenUniverseSettings) -> Option<String> {
+    let mut parts = requirement.split_whitespace();
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some("multiworld"), None, None, None) => (settings.world_settings.len() < 2)
+            .then(|| "the settings only contain a single world".to_string()),
+        (Some("goal"), Some(goal), None, None) => settings
+            .world_settings
+            .iter()
+            .any(|world_settings| {
+                !world_settings
+                    .goals
+                    .iter()
+                    .any(|world_goal| goal_name(world_goal) == goal)
+            })
+            .then(|| format!("not every world uses the {goal} goal")),
+        (Some("seedgen"), Some(comparison), Some(version), None) => {
+            let current = wotw_seedgen::VERSION;
+            let Some(ordering) = compare_versions(current, version) else {
+                return Some(format!("\"{version}\" is not a valid version"));
+            };
+            let met = match comparison {
+                ">=" => ordering.is_ge(),
+                ">" => ordering.is_gt(),
+                "<=" => ordering.is_le(),
+                "<" => ordering.is_lt(),
+                "=" | "==" => ordering.is_eq(),
+                _ => return Some(format!("\"{comparison}\" is not a valid comparison")),
+            };
+            (!met).then(|| format!("the current version is {current}"))
+        }
+        _ => Some("unknown requirement".to_string()),
+    }
+}
+/// The name used for `goal` in `#requires goal` annotations
+fn goal_name(goal: &Goal) -> &'static str {
+    match goal {
+        Goal::Wisps => "wisps",
+        Goal::Trees => "trees",
+        Goal::Quests => "quests",
+        Goal::Relics(_) | Goal::RelicChance(_) => "relics",
+    }
+}
+/// Compares two versions like `1.2.3`, missing components count as `0`
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let parse = |version: &str| {
+        version
+            .split('.')
+            .map(|component| component.parse::<u32>().ok())
+            .collect::<Option<Vec<_>>>()
+    };
+    let (mut a, mut b) = (parse(a)?, parse(b)?);
+    let length = a.len().max(b.len());
+    a.resize(length, 0);
+    b.resize(length, 0);
+    Some(a.cmp(&b))
 }
 
 #[wasm_bindgen]
@@ -65,16 +301,19 @@ impl From<SeedgenHeaderDocumentation> for HeaderDocumentation {
 /// This will only parse the minimum amount required to know the documentation
 #[wasm_bindgen]
 pub fn parse_documentation(header: &str) -> HeaderDocumentation {
-    Header::parse_documentation(header).into()
+    Header::parse_documentation(&header_syntax::seedgen_syntax(header)).into()
 }
 
 wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
     pub struct __ParameterList {
         inner: IntoIter<Parameter>,
     }
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Parameter {
     #[wasm_bindgen(getter_with_clone)]
     pub identifier: String,
@@ -83,6 +322,19 @@ pub struct Parameter {
     pub default_value: String,
     #[wasm_bindgen(getter_with_clone)]
     pub documentation: Option<String>,
+    /// Title of the section this parameter belongs to, set by a preceding `#group <title>` annotation
+    ///
+    /// `#group` applies to all following parameters until the next `#group`, an empty `#group` ends the section
+    ///
+    /// `undefined` if the parameter is not in a section
+    #[wasm_bindgen(getter_with_clone)]
+    pub group: Option<String>,
+    /// Display order of this parameter, set by an `#order <number>` annotation directly before the parameter
+    ///
+    /// `undefined` if not provided by the header
+    pub order: Option<i32>,
+    /// Only show this parameter in an advanced view, set by an `#advanced` annotation directly before the parameter
+    pub advanced: bool,
 }
 
 impl From<SeedgenParameterInfo> for Parameter {
@@ -99,12 +351,15 @@ impl From<SeedgenParameterInfo> for Parameter {
             parameter_type,
             default_value,
             documentation,
+            group: None,
+            order: None,
+            advanced: false,
         }
     }
 }
 
 #[wasm_bindgen]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ParameterType {
     Bool,
     Int,
@@ -123,15 +378,646 @@ impl From<SeedgenParameterDefault> for ParameterType {
     }
 }
 
-/// Returns the parameters present in the header, including their names and default values
+/// Returns the parameters present in the header, including their names, default values and display information
+///
+/// Parameters with an `order` come first, sorted by their `order`, followed by the remaining parameters in the order they were declared
 ///
 /// This will parse any parameter lines to read their relevant values, but skip parsing anything else
 #[wasm_bindgen]
 pub fn parse_parameters(header: &str) -> ParameterArray {
-    let parameters = Header::parse_parameters(header)
+    __ParameterList::from(parameters(header)).into_js_array()
+}
+fn parameters(header: &str) -> Vec<Parameter> {
+    let mut metadata = header_syntax::parameter_metadata(header);
+    let mut parameters = Header::parse_parameters(&header_syntax::seedgen_syntax(header))
         .into_iter()
-        .map(Parameter::from)
+        .map(|parameter_info| {
+            let mut parameter = Parameter::from(parameter_info);
+            if let Some(metadata) = metadata.remove(&parameter.identifier) {
+                parameter.group = metadata.group;
+                parameter.order = metadata.order;
+                parameter.advanced = metadata.advanced;
+            }
+            parameter
+        })
         .collect::<Vec<_>>();
+    parameters.sort_by_key(|parameter| (parameter.order.is_none(), parameter.order));
+
+    parameters
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __HeaderDependencyList {
+        inner: IntoIter<HeaderDependency>,
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __IncludeCycleList {
+        inner: IntoIter<IncludeCycle>,
+    }
+}
+
+/// Headers reachable through the includes of a header, along with any problems found while resolving them
+#[wasm_bindgen]
+pub struct HeaderDependencies {
+    headers: __HeaderDependencyList,
+    cycles: __IncludeCycleList,
+}
+#[wasm_bindgen]
+impl HeaderDependencies {
+    /// Every header involved, starting with the requested header itself
+    ///
+    /// Each header is only listed once, even if it is included multiple times
+    #[wasm_bindgen(getter)]
+    pub fn headers(&self) -> HeaderDependencyArray {
+        self.headers.clone().into_js_array()
+    }
+    /// Chains of includes that lead back to a header which is already being included
+    #[wasm_bindgen(getter)]
+    pub fn cycles(&self) -> IncludeCycleArray {
+        self.cycles.clone().into_js_array()
+    }
+}
+
+/// A header found while resolving includes
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct HeaderDependency {
+    /// The name of this header, as used to include it
+    #[wasm_bindgen(getter_with_clone)]
+    pub name: String,
+    /// `true` if this header could not be read using the provided `file_access`
+    pub missing: bool,
+    /// `true` if this header is annotated with `hide`, see `Annotations`
+    pub hide: bool,
+    /// Names of the headers directly included by this header
+    #[wasm_bindgen(getter_with_clone)]
+    pub includes: Vec<String>,
+}
 
-    __ParameterList::from(parameters).into_js_array()
+/// A chain of includes leading back to its first header
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct IncludeCycle {
+    /// Names of the headers in the order they include each other, the last one includes the first one again
+    #[wasm_bindgen(getter_with_clone)]
+    pub headers: Vec<String>,
+}
+
+/// Resolves the includes of a header recursively and returns the resulting dependency graph
+///
+/// Headers that cannot be read are reported as `missing` instead of throwing
+#[wasm_bindgen]
+pub fn header_dependencies(name: &str, file_access: &JsFileAccess) -> HeaderDependencies {
+    let mut resolver = DependencyResolver {
+        file_access,
+        headers: vec![],
+        cycles: vec![],
+        stack: vec![],
+    };
+    resolver.resolve(name);
+
+    HeaderDependencies {
+        headers: __HeaderDependencyList::from(resolver.headers),
+        cycles: __IncludeCycleList::from(resolver.cycles),
+    }
+}
+struct DependencyResolver<'a> {
+    file_access: &'a JsFileAccess,
+    headers: Vec<HeaderDependency>,
+    cycles: Vec<IncludeCycle>,
+    stack: Vec<String>,
+}
+impl DependencyResolver<'_> {
+    fn resolve(&mut self, name: &str) {
+        if let Some(cycle_start) = self.stack.iter().position(|header| header == name) {
+            let headers = self.stack[cycle_start..].to_vec();
+            self.cycles.push(IncludeCycle { headers });
+            return;
+        }
+        if self.headers.iter().any(|header| header.name == name) {
+            return;
+        }
+
+        let (missing, hide, includes) = match self.file_access.read_header(name) {
+            Ok(header) => {
+                let hide = Header::parse_annotations(&header_syntax::seedgen_syntax(&header))
+                    .map(|annotations| Annotations::from(annotations).hide)
+                    .unwrap_or_default();
+                (false, hide, header_syntax::includes(&header))
+            }
+            Err(_) => (true, false, vec![]),
+        };
+        self.headers.push(HeaderDependency {
+            name: name.to_owned(),
+            missing,
+            hide,
+            includes: includes.clone(),
+        });
+
+        self.stack.push(name.to_owned());
+        for include in includes {
+            self.resolve(&include);
+        }
+        self.stack.pop();
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __PickupPreviewList {
+        inner: IntoIter<PickupPreview>,
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __SpawnStatePreviewList {
+        inner: IntoIter<SpawnStatePreview>,
+    }
+}
+
+/// Summary of the effects a header, including everything it includes, has on a seed
+#[wasm_bindgen]
+pub struct HeaderPreview {
+    /// Items added to the item pool in header syntax, once for every copy added
+    #[wasm_bindgen(getter_with_clone)]
+    pub pool_additions: Vec<String>,
+    /// Items removed from the item pool in header syntax, once for every copy removed
+    #[wasm_bindgen(getter_with_clone)]
+    pub pool_removals: Vec<String>,
+    /// Flags set by the header
+    #[wasm_bindgen(getter_with_clone)]
+    pub flags: Vec<String>,
+    pub(crate) pickups: Vec<PickupPreview>,
+    pub(crate) spawn_states: Vec<SpawnStatePreview>,
+}
+#[wasm_bindgen]
+impl HeaderPreview {
+    /// Pickups placed by the header
+    ///
+    /// Pickups on locations other than spawn take the place of the item the randomizer would have placed there
+    #[wasm_bindgen(getter)]
+    pub fn pickups(&self) -> PickupPreviewArray {
+        __PickupPreviewList::from(self.pickups.clone()).into_js_array()
+    }
+    /// Uber states set on spawn
+    #[wasm_bindgen(getter)]
+    pub fn spawn_states(&self) -> SpawnStatePreviewArray {
+        __SpawnStatePreviewList::from(self.spawn_states.clone()).into_js_array()
+    }
+}
+
+/// A pickup placed by a header
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct PickupPreview {
+    /// The location of this pickup as `group|id`, `3|0` being spawn
+    #[wasm_bindgen(getter_with_clone)]
+    pub location: String,
+    /// The placed item in header syntax
+    #[wasm_bindgen(getter_with_clone)]
+    pub item: String,
+    /// The name of the included header this pickup comes from
+    ///
+    /// `undefined` if the pickup comes from the previewed header itself
+    #[wasm_bindgen(getter_with_clone)]
+    pub header: Option<String>,
+}
+
+/// An uber state set on spawn
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct SpawnStatePreview {
+    /// The uber state as `group|id`
+    #[wasm_bindgen(getter_with_clone)]
+    pub uber_state: String,
+    /// The value the uber state is set to
+    #[wasm_bindgen(getter_with_clone)]
+    pub value: String,
+}
+
+/// Returns a summary of what a given header syntax does to a seed
+///
+/// The header is compiled by seedgen for `world_settings`, `parameter_values` may override parameter defaults,
+/// with each entry following the syntax `identifier=value`
+///
+/// Includes are compiled as well using the provided `file_access`, included headers always use their default parameter values
+///
+/// @throws {string} if `parameter_values` contains unknown parameters, the header or an included header fails to compile or cannot be found
+#[wasm_bindgen]
+pub fn preview_header(
+    source: &str,
+    parameter_values: Vec<String>,
+    world_settings: &WorldSettings,
+    file_access: &JsFileAccess,
+) -> Result<HeaderPreview, String> {
+    compile_preview(source, parameter_values, &world_settings.0, file_access)
+}
+pub(crate) fn compile_preview(
+    source: &str,
+    parameter_values: Vec<String>,
+    settings: &SeedgenWorldSettings,
+    file_access: &JsFileAccess,
+) -> Result<HeaderPreview, String> {
+    let values = parameter_values_with_defaults(source, parameter_values)?;
+    let mut builder = PreviewBuilder {
+        file_access,
+        settings,
+        visited: vec![],
+        pool_additions: vec![],
+        pool_removals: vec![],
+        flags: vec![],
+        pickups: vec![],
+        spawn_states: vec![],
+    };
+    builder.preview(source, values, None)?;
+
+    Ok(HeaderPreview {
+        pool_additions: builder.pool_additions,
+        pool_removals: builder.pool_removals,
+        flags: builder.flags,
+        pickups: builder.pickups,
+        spawn_states: builder.spawn_states,
+    })
+}
+/// Returns the default values of all parameters in the header, overridden by `parameter_values` in the syntax `identifier=value`
+fn parameter_values_with_defaults(
+    header: &str,
+    parameter_values: Vec<String>,
+) -> Result<HashMap<String, String>, String> {
+    let mut values = Header::parse_parameters(&header_syntax::seedgen_syntax(header))
+        .into_iter()
+        .map(|parameter| (parameter.identifier, parameter.default.to_string()))
+        .collect::<HashMap<_, _>>();
+
+    for parameter_value in parameter_values {
+        let (identifier, value) = parameter_value
+            .split_once('=')
+            .ok_or_else(|| format!("expected identifier=value, got \"{parameter_value}\""))?;
+        let default = values
+            .get_mut(identifier.trim())
+            .ok_or_else(|| format!("unknown parameter \"{}\"", identifier.trim()))?;
+        *default = value.trim().to_owned();
+    }
+
+    Ok(values)
+}
+/// The trigger of pickups granted on spawn
+const SPAWN: UberIdentifier = UberIdentifier {
+    uber_group: 3,
+    uber_id: 0,
+};
+
+struct PreviewBuilder<'a> {
+    file_access: &'a JsFileAccess,
+    settings: &'a SeedgenWorldSettings,
+    visited: Vec<String>,
+    pool_additions: Vec<String>,
+    pool_removals: Vec<String>,
+    flags: Vec<String>,
+    pickups: Vec<PickupPreview>,
+    spawn_states: Vec<SpawnStatePreview>,
+}
+impl PreviewBuilder<'_> {
+    /// Compiles `source` and adds its effects, `header` is the name of the included header `source` belongs to
+    fn preview(
+        &mut self,
+        source: &str,
+        values: HashMap<String, String>,
+        header: Option<&str>,
+    ) -> Result<(), String> {
+        let build = Header::parse(header_syntax::seedgen_syntax(source))
+            .and_then(|parsed| parsed.build(values, self.settings))
+            .map_err(|err| match header {
+                Some(name) => format!("failed to compile included header \"{name}\": {err}"),
+                None => err,
+            })?;
+
+        let mut pool_changes = build
+            .item_pool_changes
+            .iter()
+            .map(|(item, amount)| (item.code(), *amount))
+            .collect::<Vec<_>>();
+        pool_changes.sort();
+        for (item, amount) in pool_changes {
+            let changes = if amount > 0 {
+                &mut self.pool_additions
+            } else {
+                &mut self.pool_removals
+            };
+            changes.extend(std::iter::repeat_n(item, amount.unsigned_abs() as usize));
+        }
+        self.flags.extend(build.flags);
+        for (trigger, item) in build.preplacements {
+            match item {
+                Item::UberState(uber_state) if trigger.identifier == SPAWN => {
+                    self.spawn_states.push(SpawnStatePreview {
+                        uber_state: uber_state.identifier.to_string(),
+                        value: uber_state.value,
+                    })
+                }
+                item => self.pickups.push(PickupPreview {
+                    location: trigger.to_string(),
+                    item: item.code(),
+                    header: header.map(str::to_owned),
+                }),
+            }
+        }
+        for include in build.includes {
+            self.include(&include)?;
+        }
+
+        Ok(())
+    }
+    fn include(&mut self, name: &str) -> Result<(), String> {
+        if self.visited.iter().any(|visited| visited == name) {
+            return Ok(());
+        }
+        self.visited.push(name.to_owned());
+
+        let source = self
+            .file_access
+            .read_header(name)
+            .map_err(|err| format!("failed to read included header \"{name}\": {err}"))?;
+        let values = parameter_values_with_defaults(&source, vec![])?;
+        self.preview(&source, values, Some(name))
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __ParameterChangeList {
+        inner: IntoIter<ParameterChange>,
+    }
+}
+
+/// Semantic differences between two versions of a header
+#[wasm_bindgen]
+pub struct HeaderDiff {
+    /// The new value of the `hide` annotation
+    ///
+    /// `undefined` if it did not change
+    pub hide: Option<bool>,
+    /// The change to the `category` annotation
+    ///
+    /// `undefined` if it did not change
+    #[wasm_bindgen(getter_with_clone)]
+    pub category: Option<TextChange>,
+    /// The change to the documented name
+    ///
+    /// `undefined` if it did not change
+    #[wasm_bindgen(getter_with_clone)]
+    pub name: Option<TextChange>,
+    /// The change to the documented description
+    ///
+    /// `undefined` if it did not change
+    #[wasm_bindgen(getter_with_clone)]
+    pub description: Option<TextChange>,
+    /// `#requires` annotations which are only present in the new version
+    #[wasm_bindgen(getter_with_clone)]
+    pub added_requirements: Vec<String>,
+    /// `#requires` annotations which are only present in the old version
+    #[wasm_bindgen(getter_with_clone)]
+    pub removed_requirements: Vec<String>,
+    /// Lines which are only present in the new version, except parameter declarations and the annotations compared separately
+    #[wasm_bindgen(getter_with_clone)]
+    pub added_commands: Vec<String>,
+    /// Lines which are only present in the old version, except parameter declarations and the annotations compared separately
+    #[wasm_bindgen(getter_with_clone)]
+    pub removed_commands: Vec<String>,
+    added_parameters: __ParameterList,
+    removed_parameters: __ParameterList,
+    changed_parameters: __ParameterChangeList,
+}
+#[wasm_bindgen]
+impl HeaderDiff {
+    /// Parameters which are only present in the new version
+    #[wasm_bindgen(getter)]
+    pub fn added_parameters(&self) -> ParameterArray {
+        self.added_parameters.clone().into_js_array()
+    }
+    /// Parameters which are only present in the old version
+    #[wasm_bindgen(getter)]
+    pub fn removed_parameters(&self) -> ParameterArray {
+        self.removed_parameters.clone().into_js_array()
+    }
+    /// Parameters present in both versions with a different type, default value, documentation or display information
+    #[wasm_bindgen(getter)]
+    pub fn changed_parameters(&self) -> ParameterChangeArray {
+        self.changed_parameters.clone().into_js_array()
+    }
+}
+
+/// The old and new version of an optional text
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct TextChange {
+    /// `undefined` if the text was added
+    #[wasm_bindgen(getter_with_clone)]
+    pub old: Option<String>,
+    /// `undefined` if the text was removed
+    #[wasm_bindgen(getter_with_clone)]
+    pub new: Option<String>,
+}
+impl TextChange {
+    fn between(old: Option<String>, new: Option<String>) -> Option<Self> {
+        (old != new).then_some(Self { old, new })
+    }
+}
+
+/// The old and new version of a `Parameter` with the same identifier
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct ParameterChange {
+    /// The `Parameter` as declared in the old version
+    #[wasm_bindgen(getter_with_clone)]
+    pub old: Parameter,
+    /// The `Parameter` as declared in the new version
+    #[wasm_bindgen(getter_with_clone)]
+    pub new: Parameter,
+}
+
+/// Compares two versions of a header syntax and returns what changed in their annotations, documentation, parameters and commands
+///
+/// @throws {string} if the annotations of either version fail to parse
+#[wasm_bindgen]
+pub fn diff_headers(old: &str, new: &str) -> Result<HeaderDiff, String> {
+    let old_annotations = parse_annotations(old)?;
+    let new_annotations = parse_annotations(new)?;
+    let old_documentation = parse_documentation(old);
+    let new_documentation = parse_documentation(new);
+
+    let old_parameters = parameters(old);
+    let mut new_parameters = parameters(new);
+    let mut removed_parameters = vec![];
+    let mut changed_parameters = vec![];
+    for old in old_parameters {
+        match new_parameters
+            .iter()
+            .position(|new| new.identifier == old.identifier)
+        {
+            Some(index) => {
+                let new = new_parameters.remove(index);
+                if old.parameter_type != new.parameter_type
+                    || old.default_value != new.default_value
+                    || old.documentation != new.documentation
+                    || old.group != new.group
+                    || old.order != new.order
+                    || old.advanced != new.advanced
+                {
+                    changed_parameters.push(ParameterChange { old, new });
+                }
+            }
+            None => removed_parameters.push(old),
+        }
+    }
+
+    let (added_requirements, removed_requirements) =
+        list_changes(old_annotations.requires, new_annotations.requires);
+    let (added_commands, removed_commands) = list_changes(commands(old), commands(new));
+
+    Ok(HeaderDiff {
+        hide: (old_annotations.hide != new_annotations.hide).then_some(new_annotations.hide),
+        category: TextChange::between(old_annotations.category, new_annotations.category),
+        name: TextChange::between(old_documentation.name, new_documentation.name),
+        description: TextChange::between(
+            old_documentation.description,
+            new_documentation.description,
+        ),
+        added_requirements,
+        removed_requirements,
+        added_commands,
+        removed_commands,
+        added_parameters: __ParameterList::from(new_parameters),
+        removed_parameters: __ParameterList::from(removed_parameters),
+        changed_parameters: __ParameterChangeList::from(changed_parameters),
+    })
+}
+/// Annotations which `diff_headers` compares through the `Annotations` or `Parameter`s instead of as commands
+const COMPARED_ANNOTATIONS: [&str; 6] = [
+    "#hide",
+    "#category",
+    "#requires",
+    "#group",
+    "#order",
+    "#advanced",
+];
+/// Returns all lines of the header except the `COMPARED_ANNOTATIONS` and parameter declarations
+fn commands(header: &str) -> Vec<String> {
+    header_syntax::lines(header)
+        .filter(|line| {
+            !header_syntax::is_annotation(line.content, &COMPARED_ANNOTATIONS)
+                && !matches!(line.command(), Some(("parameter", _)))
+        })
+        .map(|line| line.content.to_owned())
+        .collect()
+}
+/// Returns the entries only present in `new` and the entries only present in `old`, repeated entries are counted
+fn list_changes(old: Vec<String>, new: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut added = new;
+    let mut removed = vec![];
+    for entry in old {
+        match added.iter().position(|added| *added == entry) {
+            Some(index) => {
+                added.remove(index);
+            }
+            None => removed.push(entry),
+        }
+    }
+    (added, removed)
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __TextEditList {
+        inner: IntoIter<TextEdit>,
+    }
+}
+
+/// A replacement of text on a single line
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct TextEdit {
+    /// Zero-based index of the line to edit
+    pub line: usize,
+    /// Column of the first character to replace, in UTF-16 code units
+    pub start: usize,
+    /// Column after the last character to replace, in UTF-16 code units
+    pub end: usize,
+    /// The text to insert instead
+    #[wasm_bindgen(getter_with_clone)]
+    pub new_text: String,
+}
+
+/// Returns the `TextEdit`s needed to rename the parameter `old` to `new` in a given header syntax
+///
+/// This covers the declaration and every `$PARAM` reference, references inside comments are left untouched
+///
+/// @throws {string} if `old` is not declared, `new` is not a valid identifier or `new` is already declared
+#[wasm_bindgen]
+pub fn rename_header_parameter(
+    source: &str,
+    old: &str,
+    new: &str,
+) -> Result<TextEditArray, String> {
+    let parameters = Header::parse_parameters(&header_syntax::seedgen_syntax(source));
+    if !parameters
+        .iter()
+        .any(|parameter| parameter.identifier == old)
+    {
+        return Err(format!("there is no parameter \"{old}\""));
+    }
+    if new.is_empty() || !new.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(format!("\"{new}\" is not a valid parameter identifier"));
+    }
+    if parameters
+        .iter()
+        .any(|parameter| parameter.identifier == new)
+    {
+        return Err(format!("there already is a parameter \"{new}\""));
+    }
+
+    let edits = header_syntax::parameter_spans(source, old)
+        .into_iter()
+        .map(|span| TextEdit {
+            line: span.line,
+            start: span.start,
+            end: span.end,
+            new_text: new.to_owned(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(__TextEditList::from(edits).into_js_array())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_by_component() {
+        assert_eq!(compare_versions("1.2.3", "1.2.3"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("1.10", "1.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("0.9.1", "1"), Some(Ordering::Less));
+        // Missing components count as 0
+        assert_eq!(compare_versions("2", "2.0.0"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("2.1", "2.0.5"), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn invalid_versions_are_not_compared() {
+        assert_eq!(compare_versions("1.x", "1.0"), None);
+        assert_eq!(compare_versions("1.0", "1.0-beta"), None);
+        assert_eq!(compare_versions("", "1"), None);
+    }
 }
@@ -1,7 +1,18 @@
+mod areas;
+pub mod export;
 pub mod files;
+pub mod graph_diff;
+mod header_syntax;
+pub mod header_tests;
 pub mod headers;
+pub mod lint;
 pub mod map;
+pub mod reach;
+pub mod requirement;
+pub mod search;
 pub mod settings;
+pub mod spatial;
+pub mod uber_states;
 
 use wasm_bindgen::prelude::*;
 
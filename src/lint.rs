@@ -0,0 +1,631 @@
+use std::collections::{HashMap, HashSet};
+
+use wasm_bindgen::prelude::*;
+
+use wotw_seedgen::header::Header;
+use wotw_seedgen::preset::UniversePreset as SeedgenUniversePreset;
+use wotw_seedgen::preset::WorldPreset as SeedgenWorldPreset;
+use wotw_seedgen::settings::WorldSettings as SeedgenWorldSettings;
+use wotw_seedgen::settings::{Difficulty, UniverseSettings as SeedgenUniverseSettings};
+use wotw_seedgen::util::Position as SeedgenPosition;
+use wotw_seedgen::world::graph::{Node as SeedgenNode, Requirement as SeedgenRequirement};
+
+use wasm_bindgen_helper_macros::*;
+
+use crate::areas;
+use crate::header_syntax;
+
+/// Configuration for the lint passes
+#[wasm_bindgen]
+pub struct LintConfig {
+    allowed: Vec<String>,
+}
+#[wasm_bindgen]
+impl LintConfig {
+    /// Creates a new `LintConfig`
+    ///
+    /// Every entry of `allowed` suppresses warnings, either all warnings with a code (e.g. `"unused-parameter"`)
+    /// or only the warning with a code on a specific zero-based line of a `LintSource` (e.g. `"unused-parameter:header:12"`)
+    #[wasm_bindgen(constructor)]
+    pub fn new(allowed: Vec<String>) -> Self {
+        Self { allowed }
+    }
+}
+impl LintConfig {
+    fn allows(&self, warning: &LintWarning) -> bool {
+        let on_line = format!(
+            "{}:{}:{}",
+            warning.code,
+            warning.source.name(),
+            warning.line
+        );
+        self.allowed
+            .iter()
+            .any(|allowed| *allowed == warning.code || *allowed == on_line)
+    }
+}
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// The input a `LintWarning` refers to
+    pub enum LintSource {
+        /// The header passed to `lint_header`
+        Header,
+        /// The preset json passed to `lint_world_preset` or `lint_universe_preset`
+        Preset,
+        /// The `areas` passed to `lint_logic`
+        Areas,
+        /// The `locations` passed to `lint_logic`
+        Locations,
+    }
+}
+impl LintSource {
+    /// The name used for this source in the entries of a `LintConfig`
+    fn name(self) -> &'static str {
+        match self {
+            LintSource::Header => "header",
+            LintSource::Preset => "preset",
+            LintSource::Areas => "areas",
+            LintSource::Locations => "locations",
+        }
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __LintWarningList {
+        inner: IntoIter<LintWarning>,
+    }
+}
+
+/// A likely mistake found by a lint pass
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct LintWarning {
+    /// Identifies the kind of mistake, e.g. `unused-parameter`, use it to suppress warnings through the `LintConfig`
+    #[wasm_bindgen(getter_with_clone)]
+    pub code: String,
+    /// Human-readable explanation
+    #[wasm_bindgen(getter_with_clone)]
+    pub message: String,
+    /// Zero-based index of the line the mistake is on
+    pub line: usize,
+    /// Column of the first character of the mistake, in UTF-16 code units
+    pub start: usize,
+    /// Column after the last character of the mistake, in UTF-16 code units
+    pub end: usize,
+    source: LintSource,
+}
+#[wasm_bindgen]
+impl LintWarning {
+    /// The input `line`, `start` and `end` refer to
+    #[wasm_bindgen(getter)]
+    pub fn source(&self) -> LintSourceEnum {
+        self.source.into_js_enum()
+    }
+}
+impl LintWarning {
+    /// Creates a `LintWarning` spanning the trimmed content of `line` in `text`, which is the input identified by `source`
+    fn on_line(text: &str, source: LintSource, line: usize, code: &str, message: String) -> Self {
+        let text = text.lines().nth(line).unwrap_or_default();
+        let content = text.trim();
+        let start = text[..text.len() - text.trim_start().len()]
+            .encode_utf16()
+            .count();
+        let end = start + content.encode_utf16().count();
+        Self {
+            code: code.to_owned(),
+            message,
+            line,
+            start,
+            end,
+            source,
+        }
+    }
+}
+
+/// Returns warnings about likely mistakes in a given header syntax
+///
+/// Warnings may also be suppressed by a `// allow: <code>` comment on the line of the warning or the line before.
+/// The following codes are used:
+/// - `unused-parameter`: a parameter is declared but never referenced through `$PARAM`
+/// - `undocumented-parameter`: a parameter has no documentation
+/// - `hidden-category`: a header annotated with `hide` also has a `category`, which will never be shown
+/// - `invalid-order`: an `#order` annotation has a value that isn't an integer, so it is ignored
+#[wasm_bindgen]
+pub fn lint_header(header: &str, config: &LintConfig) -> LintWarningArray {
+    let mut warnings = vec![];
+
+    for parameter in Header::parse_parameters(&header_syntax::seedgen_syntax(header)) {
+        let spans = header_syntax::parameter_spans(header, &parameter.identifier);
+        let Some(declaration) = spans.first() else {
+            continue;
+        };
+        let mut warn = |code: &str, message: String| {
+            warnings.push(LintWarning {
+                code: code.to_owned(),
+                message,
+                line: declaration.line,
+                start: declaration.start,
+                end: declaration.end,
+                source: LintSource::Header,
+            })
+        };
+
+        if spans.len() == 1 {
+            warn(
+                "unused-parameter",
+                format!(
+                    "the parameter \"{}\" is never referenced",
+                    parameter.identifier
+                ),
+            );
+        }
+        if parameter.documentation.is_none() {
+            warn(
+                "undocumented-parameter",
+                format!(
+                    "the parameter \"{}\" has no documentation",
+                    parameter.identifier
+                ),
+            );
+        }
+    }
+
+    let annotations = header_syntax::lines(header)
+        .filter(|line| line.content.starts_with('#'))
+        .collect::<Vec<_>>();
+    if annotations.iter().any(|line| line.content == "#hide") {
+        if let Some(category) = annotations
+            .iter()
+            .find(|line| line.content.starts_with("#category"))
+        {
+            warnings.push(LintWarning::on_line(
+                header,
+                LintSource::Header,
+                category.line,
+                "hidden-category",
+                "the header is hidden, so its category will never be shown".to_string(),
+            ));
+        }
+    }
+
+    for order in header_syntax::invalid_orders(header) {
+        warnings.push(LintWarning::on_line(
+            header,
+            LintSource::Header,
+            order.line,
+            "invalid-order",
+            format!(
+                "\"{}\" needs an integer value, it will be ignored",
+                order.content
+            ),
+        ));
+    }
+
+    let lines = header.lines().collect::<Vec<_>>();
+    let warnings = warnings
+        .into_iter()
+        .filter(|warning| {
+            let allow_comment = format!("// allow: {}", warning.code);
+            let suppressed = [Some(warning.line), warning.line.checked_sub(1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|line| lines.get(line))
+                .any(|line| line.contains(&allow_comment));
+            !suppressed && !config.allows(warning)
+        })
+        .collect::<Vec<_>>();
+
+    __LintWarningList::from(warnings).into_js_array()
+}
+
+/// Returns warnings about likely mistakes in a given `WorldPreset` json
+///
+/// The following codes are used:
+/// - `default-value`: the preset sets a value to the default it would have anyway
+///
+/// @throws {string} if the input fails to deserialize
+#[wasm_bindgen]
+pub fn lint_world_preset(json: &str, config: &LintConfig) -> Result<LintWarningArray, String> {
+    let preset = SeedgenWorldPreset::parse(json).map_err(|err| err.to_string())?;
+    let mut warnings = vec![];
+    let object = json.find('{');
+    lint_world_preset_defaults(json, &preset, object, "", &mut warnings);
+
+    Ok(filter_allowed(warnings, config))
+}
+
+/// Returns warnings about likely mistakes in a given `UniversePreset` json
+///
+/// See `lint_world_preset` for the codes used
+///
+/// @throws {string} if the input fails to deserialize
+#[wasm_bindgen]
+pub fn lint_universe_preset(json: &str, config: &LintConfig) -> Result<LintWarningArray, String> {
+    let preset = SeedgenUniversePreset::parse(json).map_err(|err| err.to_string())?;
+    let mut warnings = vec![];
+    let objects = json
+        .find('{')
+        .and_then(|root| key_index(json, root, "worldSettings"))
+        .and_then(|key| value_index(json, key))
+        .map(|array| members(json, array))
+        .unwrap_or_default();
+    for (index, world_preset) in preset.world_settings.iter().flatten().enumerate() {
+        let path = format!("worldSettings[{index}].");
+        let object = objects.get(index).copied();
+        lint_world_preset_defaults(json, world_preset, object, &path, &mut warnings);
+    }
+
+    Ok(filter_allowed(warnings, config))
+}
+
+fn filter_allowed(warnings: Vec<LintWarning>, config: &LintConfig) -> LintWarningArray {
+    let warnings = warnings
+        .into_iter()
+        .filter(|warning| !config.allows(warning))
+        .collect::<Vec<_>>();
+    __LintWarningList::from(warnings).into_js_array()
+}
+
+/// Warns about values in the preset that match the defaults
+///
+/// `object` is the byte index in `json` of the object holding the `preset`, the warnings point at its keys.
+/// `path` leads to the `object` and prefixes the keys in the messages, e.g. `worldSettings[1].`
+/// If the `object` or a key can't be found, the warning is placed on the first line
+fn lint_world_preset_defaults(
+    json: &str,
+    preset: &SeedgenWorldPreset,
+    object: Option<usize>,
+    path: &str,
+    warnings: &mut Vec<LintWarning>,
+) {
+    let defaults = SeedgenWorldSettings::default();
+    let default_keys = [
+        ("difficulty", preset.difficulty == Some(defaults.difficulty)),
+        ("hard", preset.hard == Some(defaults.hard)),
+        (
+            "tricks",
+            preset
+                .tricks
+                .as_ref()
+                .is_some_and(|tricks| tricks.is_empty()),
+        ),
+        (
+            "goals",
+            preset.goals.as_ref().is_some_and(|goals| goals.is_empty()),
+        ),
+        (
+            "headers",
+            preset
+                .headers
+                .as_ref()
+                .is_some_and(|headers| headers.is_empty()),
+        ),
+    ];
+
+    for (key, is_default) in default_keys {
+        if !is_default {
+            continue;
+        }
+        let index = object
+            .and_then(|object| key_index(json, object, key))
+            .unwrap_or_default();
+        let line = json[..index].matches('\n').count();
+        warnings.push(LintWarning::on_line(
+            json,
+            LintSource::Preset,
+            line,
+            "default-value",
+            format!("\"{path}{key}\" is set to its default value and can be removed"),
+        ));
+    }
+}
+
+/// Returns the byte indices of the members of the JSON object or array starting at the byte index `container`
+///
+/// For objects, these are the indices of the keys
+fn members(json: &str, container: usize) -> Vec<usize> {
+    let bytes = json.as_bytes();
+    let mut members = vec![];
+    let mut depth = 0;
+    let mut expecting_member = true;
+    let mut index = container;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if depth == 1
+            && expecting_member
+            && !byte.is_ascii_whitespace()
+            && !matches!(byte, b',' | b'}' | b']')
+        {
+            members.push(index);
+            expecting_member = false;
+        }
+        match byte {
+            b'"' => {
+                index += 1;
+                while index < bytes.len() && bytes[index] != b'"' {
+                    if bytes[index] == b'\\' {
+                        index += 1;
+                    }
+                    index += 1;
+                }
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            b',' if depth == 1 => expecting_member = true,
+            _ => {}
+        }
+        index += 1;
+    }
+    members
+}
+/// Returns the byte index of `key` in the JSON object starting at the byte index `object`, ignoring nested objects
+fn key_index(json: &str, object: usize, key: &str) -> Option<usize> {
+    let quoted_key = format!("\"{key}\"");
+    members(json, object)
+        .into_iter()
+        .find(|member| json[*member..].starts_with(&quoted_key))
+}
+/// Returns the byte index of the value belonging to the key at the byte index `key`
+fn value_index(json: &str, key: usize) -> Option<usize> {
+    let colon = key + json[key + 1..].find('"')? + 2;
+    let colon = colon + json[colon..].find(':')?;
+    let value = json[colon + 1..].find(|c: char| !c.is_whitespace())?;
+    Some(colon + 1 + value)
+}
+
+/// Distance in in-game units within which another anchor counts as nearby, see `lint_logic`
+const NEARBY_DISTANCE: f32 = 30.;
+
+/// Returns warnings about structural problems in the given logic files
+///
+/// `areas` should be in the syntax usually used by `areas.wotw`, `locations` and `states` should provide csv data as usually used by `loc_data.csv` and `state_data.csv`
+///
+/// The following codes are used:
+/// - `unreachable-anchor`: an anchor can't be reached from any anchor that can be used as spawn, even with every item and under unsafe logic
+/// - `dead-end-anchor`: an anchor has no `conn` lines leading to other anchors
+/// - `one-way-connection`: a `conn` line leads to an anchor that has no way back, neither directly nor between anchors nearby either end
+/// - `duplicate-connection`: an anchor contains multiple lines leading to the same target
+/// - `unknown-pickup`: a `pickup` line refers to a location missing from `locations`
+/// - `missing-pickup`: a location in `locations` has no `pickup` line
+///
+/// `missing-pickup` warnings refer to `LintSource.Locations`, all other warnings refer to `LintSource.Areas`
+///
+/// @throws {string} if the logic fails to parse
+#[wasm_bindgen]
+pub fn lint_logic(
+    areas: &str,
+    locations: &str,
+    states: &str,
+    config: &LintConfig,
+) -> Result<LintWarningArray, String> {
+    let mut settings = SeedgenUniverseSettings::default();
+    settings.world_settings[0].difficulty = Difficulty::Unsafe;
+    let logic = wotw_seedgen::logic::parse_logic(areas, locations, states, &settings, false)?;
+
+    let definitions = areas::anchors(areas);
+    let mut warnings = vec![];
+    let mut warn = |line: usize, code: &str, message: String| {
+        warnings.push(LintWarning::on_line(
+            areas,
+            LintSource::Areas,
+            line,
+            code,
+            message,
+        ))
+    };
+
+    let reachable = reachable_anchors(&logic.nodes);
+    let neighbourhoods = neighbourhoods(&logic.nodes);
+    let exits = |name: &str| {
+        definitions
+            .get(name)
+            .into_iter()
+            .flat_map(|definition| &definition.connections)
+            .filter(|connection| connection.keyword == "conn")
+    };
+    for (name, definition) in &definitions {
+        let line = definition.range.start.line;
+        if !reachable.contains(name.as_str()) {
+            warn(
+                line,
+                "unreachable-anchor",
+                format!("the anchor \"{name}\" can't be reached from any spawn"),
+            );
+        }
+
+        if exits(name).next().is_none() {
+            warn(
+                line,
+                "dead-end-anchor",
+                format!("the anchor \"{name}\" has no connections to other anchors"),
+            );
+        }
+        for connection in exits(name) {
+            // The way back may start at an anchor near the target and arrive at an anchor near the start
+            let back_ends = neighbourhoods.get(name.as_str());
+            let leads_back = neighbourhoods
+                .get(connection.target.as_str())
+                .into_iter()
+                .flatten()
+                .any(|back_start| {
+                    exits(back_start).any(|back| {
+                        back_ends.is_some_and(|back_ends| back_ends.contains(back.target.as_str()))
+                    })
+                });
+            if !leads_back {
+                warn(
+                    connection.range.start.line,
+                    "one-way-connection",
+                    format!(
+                        "there is no way back from \"{}\" or any anchor near it to \"{name}\"",
+                        connection.target
+                    ),
+                );
+            }
+        }
+
+        let mut targets = HashSet::new();
+        for connection in &definition.connections {
+            if !targets.insert((connection.keyword, connection.target.as_str())) {
+                warn(
+                    connection.range.start.line,
+                    "duplicate-connection",
+                    format!(
+                        "the anchor \"{name}\" already has a {} line for \"{}\"",
+                        connection.keyword, connection.target
+                    ),
+                );
+            }
+        }
+    }
+
+    let location_lines = locations
+        .lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let identifier = text.split(',').next()?.trim();
+            (!identifier.is_empty()).then_some((identifier, line))
+        })
+        .collect::<HashMap<_, _>>();
+    let pickups = definitions
+        .values()
+        .flat_map(|definition| &definition.connections)
+        .filter(|connection| connection.keyword == "pickup")
+        .collect::<Vec<_>>();
+    for pickup in &pickups {
+        if !location_lines.contains_key(pickup.target.as_str()) {
+            warn(
+                pickup.range.start.line,
+                "unknown-pickup",
+                format!(
+                    "the location \"{}\" is missing from the locations",
+                    pickup.target
+                ),
+            );
+        }
+    }
+    let pickup_names = pickups
+        .iter()
+        .map(|pickup| pickup.target.as_str())
+        .collect::<HashSet<_>>();
+    for (identifier, line) in location_lines {
+        if !pickup_names.contains(identifier) {
+            warnings.push(LintWarning::on_line(
+                locations,
+                LintSource::Locations,
+                line,
+                "missing-pickup",
+                format!("the location \"{identifier}\" has no pickup line in the areas"),
+            ));
+        }
+    }
+
+    warnings.sort_by_key(|warning| {
+        (
+            matches!(warning.source, LintSource::Locations),
+            warning.line,
+            warning.start,
+        )
+    });
+    Ok(filter_allowed(warnings, config))
+}
+
+/// Returns the names of every anchor reachable from any anchor that can be used as spawn, through connections that are not `impossible`
+fn reachable_anchors(nodes: &[SeedgenNode]) -> HashSet<&str> {
+    let mut reachable = HashSet::new();
+    let mut queue = nodes
+        .iter()
+        .filter(|node| matches!(node, SeedgenNode::Anchor(anchor) if anchor.can_spawn))
+        .collect::<Vec<_>>();
+    while let Some(node) = queue.pop() {
+        let SeedgenNode::Anchor(anchor) = node else {
+            continue;
+        };
+        if !reachable.insert(anchor.identifier.as_str()) {
+            continue;
+        }
+        queue.extend(
+            anchor
+                .connections
+                .iter()
+                .filter(|connection| {
+                    !matches!(connection.requirement, SeedgenRequirement::Impossible)
+                })
+                .map(|connection| &nodes[connection.to]),
+        );
+    }
+    reachable
+}
+/// Returns every anchor along with the anchors within `NEARBY_DISTANCE` of it, including itself, by name
+fn neighbourhoods(nodes: &[SeedgenNode]) -> HashMap<&str, HashSet<&str>> {
+    let anchors = nodes
+        .iter()
+        .filter_map(|node| match node {
+            SeedgenNode::Anchor(anchor) => {
+                Some((anchor.identifier.as_str(), anchor.position.as_ref()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let near = |a: Option<&SeedgenPosition>, b: Option<&SeedgenPosition>| {
+        a.zip(b).is_some_and(|(a, b)| {
+            let distance =
+                (a.x.into_inner() - b.x.into_inner()).hypot(a.y.into_inner() - b.y.into_inner());
+            distance <= NEARBY_DISTANCE
+        })
+    };
+
+    anchors
+        .iter()
+        .map(|(name, position)| {
+            let neighbourhood = anchors
+                .iter()
+                .filter(|(other, other_position)| other == name || near(*position, *other_position))
+                .map(|(other, _)| *other)
+                .collect();
+            (*name, neighbourhood)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIVERSE: &str = r#"{
+  "worldSettings": [
+    { "goals": [], "headers": ["a\"b"] },
+    {
+      "difficulty": "Moki",
+      "headers": []
+    }
+  ],
+  "headers": []
+}"#;
+
+    fn line(index: Option<usize>) -> Option<usize> {
+        index.map(|index| UNIVERSE[..index].matches('\n').count())
+    }
+
+    #[test]
+    fn keys_are_found_in_their_object() {
+        let array =
+            key_index(UNIVERSE, 0, "worldSettings").and_then(|key| value_index(UNIVERSE, key));
+        assert_eq!(line(array), Some(1));
+        let objects = members(UNIVERSE, array.unwrap());
+        assert_eq!(objects.len(), 2);
+        assert_eq!(line(key_index(UNIVERSE, objects[0], "headers")), Some(2));
+        assert_eq!(line(key_index(UNIVERSE, objects[1], "headers")), Some(5));
+        assert_eq!(key_index(UNIVERSE, objects[1], "goals"), None);
+        assert_eq!(line(key_index(UNIVERSE, 0, "headers")), Some(8));
+    }
+}
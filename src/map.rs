@@ -1,13 +1,26 @@
-use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
 use wasm_bindgen::prelude::*;
 
+use wotw_seedgen::item::Resource;
 use wotw_seedgen::settings::{Difficulty, UniverseSettings};
 use wotw_seedgen::util::NodeKind;
 use wotw_seedgen::util::Position as SeedgenPosition;
+use wotw_seedgen::util::Zone as SeedgenZone;
+use wotw_seedgen::world::graph::Anchor as SeedgenAnchor;
+use wotw_seedgen::world::graph::Connection as SeedgenConnection;
+use wotw_seedgen::world::graph::Graph as SeedgenGraph;
 use wotw_seedgen::world::graph::Node as SeedgenNode;
+use wotw_seedgen::world::graph::RefillValue;
+use wotw_seedgen::world::graph::Requirement as SeedgenRequirement;
 
 use wasm_bindgen_helper_macros::*;
 
+use crate::areas::{self, AnchorDefinition, ConnectionDefinition};
+use crate::requirement::Requirement;
+use crate::settings::WorldSettings;
+use crate::spatial::SpatialIndex;
+
 ts_enum! {
     #[wasm_bindgen]
     #[derive(Clone, Copy)]
@@ -15,26 +28,281 @@ ts_enum! {
     pub enum ConnectionType {
         /// Anchor-to-anchor `Connection`
         Branch,
-        /// Anchor-to-pickup `Connection`
+        /// Anchor-to-pickup or anchor-to-quest `Connection`
         Leaf,
+        /// Anchor-to-anchor `Connection` using a teleporter, i.e. every alternative of its declared requirement needs a teleporter
+        Teleporter,
+        /// Anchor-to-anchor `Connection` through a keystone door, i.e. every alternative of its declared requirement needs `Keystone`s
+        Door,
+    }
+}
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// What a `Refill` restores
+    pub enum RefillType {
+        /// Full health and energy
+        Full,
+        /// Health and energy up to the amount restored by a checkpoint
+        Checkpoint,
+        /// Health from health plants, `amount` is the number of plants
+        Health,
+        /// Energy from energy crystals, `amount` is the energy restored
+        Energy,
+    }
+}
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// What a `Node` represents in the logic
+    pub enum NodeType {
+        /// A place defined by an `anchor`
+        Anchor,
+        /// A location with a pickup
+        Pickup,
+        /// A world state, e.g. a lever
+        State,
+        /// A quest
+        Quest,
+    }
+}
+impl From<NodeKind> for NodeType {
+    fn from(kind: NodeKind) -> NodeType {
+        match kind {
+            NodeKind::Anchor => NodeType::Anchor,
+            NodeKind::Pickup => NodeType::Pickup,
+            NodeKind::State => NodeType::State,
+            NodeKind::Quest => NodeType::Quest,
+        }
+    }
+}
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// What kind of item a pickup location holds in the base game
+    pub enum LocationCategory {
+        /// A spirit shard
+        Shard,
+        /// A skill like `Dash`
+        Skill,
+        /// Spirit light, also known as experience
+        SpiritLight,
+        /// Gorlek ore
+        Ore,
+        /// A health fragment
+        Health,
+        /// An energy fragment
+        Energy,
+        /// A keystone
+        Keystone,
+        /// A shard slot upgrade
+        ShardSlot,
+        /// Any other location, e.g. quest rewards
+        Other,
+    }
+}
+/// Column of `loc_data.csv` holding the kind of a location, e.g. `Resource` or `Skill`
+const CATEGORY_COLUMN: usize = 2;
+/// Column of `loc_data.csv` holding what exactly a location gives, e.g. `Health` for a `Resource`
+const VALUE_COLUMN: usize = 3;
+impl LocationCategory {
+    /// Reads the category from the category and value columns of a row in `loc_data.csv`
+    fn from_location_data(row: &[String]) -> LocationCategory {
+        let column = |index: usize| row.get(index).map_or("", String::as_str);
+        match (column(CATEGORY_COLUMN), column(VALUE_COLUMN)) {
+            ("Shard", _) => LocationCategory::Shard,
+            ("Skill", _) => LocationCategory::Skill,
+            ("SpiritLight", _) => LocationCategory::SpiritLight,
+            ("Resource", "Health") => LocationCategory::Health,
+            ("Resource", "Energy") => LocationCategory::Energy,
+            ("Resource", "Ore") => LocationCategory::Ore,
+            ("Resource", "Keystone") => LocationCategory::Keystone,
+            ("Resource", "ShardSlot") => LocationCategory::ShardSlot,
+            _ => LocationCategory::Other,
+        }
+    }
+}
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// An area of the game world
+    pub enum Zone {
+        /// Inkwater Marsh
+        Marsh,
+        /// Kwolok's Hollow
+        Hollow,
+        /// Wellspring Glades
+        Glades,
+        /// The Wellspring
+        Wellspring,
+        /// Silent Woods
+        Woods,
+        /// Baur's Reach
+        Reach,
+        /// Mouldwood Depths
+        Depths,
+        /// Luma Pools
+        Pools,
+        /// Windswept Wastes
+        Wastes,
+        /// Windtorn Ruins
+        Ruins,
+        /// Willow's End
+        Willow,
+        /// Midnight Burrows
+        Burrows,
+        /// The starting area
+        Spawn,
+        /// Twillen's, Opher's and Lupo's shops
+        Shop,
+        /// Outside of any area
+        Void,
+    }
+}
+impl From<SeedgenZone> for Zone {
+    fn from(zone: SeedgenZone) -> Zone {
+        match zone {
+            SeedgenZone::Marsh => Zone::Marsh,
+            SeedgenZone::Hollow => Zone::Hollow,
+            SeedgenZone::Glades => Zone::Glades,
+            SeedgenZone::Wellspring => Zone::Wellspring,
+            SeedgenZone::Woods => Zone::Woods,
+            SeedgenZone::Reach => Zone::Reach,
+            SeedgenZone::Depths => Zone::Depths,
+            SeedgenZone::Pools => Zone::Pools,
+            SeedgenZone::Wastes => Zone::Wastes,
+            SeedgenZone::Ruins => Zone::Ruins,
+            SeedgenZone::Willow => Zone::Willow,
+            SeedgenZone::Burrows => Zone::Burrows,
+            SeedgenZone::Spawn => Zone::Spawn,
+            SeedgenZone::Shop => Zone::Shop,
+            SeedgenZone::Void => Zone::Void,
+        }
     }
 }
 
 #[wasm_bindgen]
 /// Set of `Node`s and `Connection`s connecting those `Node`s
 pub struct Graph {
-    nodes: __NodeList,
-    connections: __ConnectionList,
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) connections: Vec<Connection>,
+    unpositioned_nodes: Vec<UnpositionedNode>,
+    dropped_connections: Vec<DroppedConnection>,
+    pub(crate) definitions: HashMap<String, AnchorDefinition>,
+    pub(crate) spatial_index: SpatialIndex,
+    /// The logic this `Graph` was built from, including `Node`s without a map position
+    pub(crate) logic: SeedgenGraph,
 }
 #[wasm_bindgen]
 impl Graph {
     #[wasm_bindgen(getter)]
     pub fn connections(&self) -> ConnectionArray {
-        self.connections.clone().into_js_array()
+        __ConnectionList::from(self.connections.clone()).into_js_array()
     }
     #[wasm_bindgen(getter)]
     pub fn nodes(&self) -> NodeObject {
-        self.nodes.clone().into_js_object("name")
+        __NodeList::from(self.nodes.clone()).into_js_object("name")
+    }
+    #[wasm_bindgen(getter)]
+    /// Nodes from the logic which are missing from `nodes` because they have no map position
+    ///
+    /// World states are left out since they never have a map position
+    pub fn unpositioned_nodes(&self) -> UnpositionedNodeArray {
+        __UnpositionedNodeList::from(self.unpositioned_nodes.clone()).into_js_array()
+    }
+    #[wasm_bindgen(getter)]
+    /// Connections from the logic which are missing from `connections` because one of their ends has no map position
+    ///
+    /// Connections to world states are left out since those never have a map position
+    pub fn dropped_connections(&self) -> DroppedConnectionArray {
+        __DroppedConnectionList::from(self.dropped_connections.clone()).into_js_array()
+    }
+    #[wasm_bindgen]
+    /// Returns the innermost definition in the source file at the given zero-based `line` and `char`
+    ///
+    /// This is the reverse of the `source_location` methods on `Node` and `Connection`
+    ///
+    /// `undefined` if the location is outside of any anchor definition
+    pub fn element_at(&self, line: usize, char: usize) -> Option<SourceElement> {
+        let location = SourceLocation { line, char };
+        let (anchor, definition) = self
+            .definitions
+            .iter()
+            .find(|(_, definition)| definition.range.contains(location))?;
+
+        let Some(connection) = definition
+            .connections
+            .iter()
+            .find(|connection| connection.range.contains(location))
+        else {
+            return Some(SourceElement {
+                kind: SourceElementKind::Anchor,
+                anchor: anchor.clone(),
+                target: None,
+                range: definition.range,
+            });
+        };
+
+        let requirement = connection
+            .requirement_ranges
+            .iter()
+            .filter(|range| range.contains(location))
+            .min_by_key(|range| (range.end.line - range.start.line, range.end.char));
+        let (kind, range) = match (requirement, connection.keyword) {
+            (Some(range), _) => (SourceElementKind::Requirement, *range),
+            (None, "pickup") => (SourceElementKind::Pickup, connection.range),
+            (None, _) => (SourceElementKind::Connection, connection.range),
+        };
+        Some(SourceElement {
+            kind,
+            anchor: anchor.clone(),
+            target: Some(connection.target.clone()),
+            range,
+        })
+    }
+}
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// The kind of definition a `SourceElement` refers to
+    pub enum SourceElementKind {
+        /// An `anchor` definition
+        Anchor,
+        /// A `conn`, `quest`, `state` or `refill` line inside an anchor
+        Connection,
+        /// A `pickup` line leading away from an anchor
+        Pickup,
+        /// Part of the requirement of a connection
+        Requirement,
+    }
+}
+
+#[wasm_bindgen]
+/// A definition in the source file, see `Graph.element_at`
+pub struct SourceElement {
+    kind: SourceElementKind,
+    #[wasm_bindgen(getter_with_clone)]
+    /// Name of the anchor containing this definition
+    pub anchor: String,
+    #[wasm_bindgen(getter_with_clone)]
+    /// Name of the `Node` the connection leads to, or the kind of refill like `Energy` for `refill` lines
+    ///
+    /// `undefined` if the `kind` is `Anchor`
+    pub target: Option<String>,
+    /// The range of this definition in the source file
+    pub range: SourceRange,
+}
+#[wasm_bindgen]
+impl SourceElement {
+    #[wasm_bindgen(getter)]
+    /// The `SourceElementKind` of this definition
+    pub fn kind(&self) -> SourceElementKindEnum {
+        self.kind.into_js_enum()
     }
 }
 
@@ -45,10 +313,60 @@ impl Graph {
 ///
 /// @throws {string} Throws if the input fails to parse
 pub fn graph(areas: &str, locations: &str) -> Result<Graph, JsValue> {
-    let states = ""; // As long as the state data doesn't track coordinates, it isn't useful for our purpose
+    build_graph(areas, locations, |_, _| true)
+}
+
+#[wasm_bindgen]
+/// Returns a `Graph` based on the given logic files, marking which `Connection`s are in logic for the given `world_settings`
+///
+/// The `Graph` contains the same `Node`s and `Connection`s as the one returned by `graph`,
+/// use `Connection.in_logic` to find out whether a `Connection` can be used with the difficulty and tricks in `world_settings`
+///
+/// @throws {string} Throws if the input fails to parse
+pub fn logic_graph(
+    areas: &str,
+    locations: &str,
+    world_settings: &WorldSettings,
+) -> Result<Graph, JsValue> {
+    let mut settings = UniverseSettings::default();
+    settings.world_settings[0] = world_settings.0.clone();
+    let logic = wotw_seedgen::logic::parse_logic(areas, locations, STATES, &settings, false)?;
+
+    let in_logic = anchors(&logic.nodes)
+        .flat_map(|anchor| {
+            anchor
+                .connections
+                .iter()
+                .filter(|connection| !connection.implicitly_generated)
+                // seedgen keeps connections whose requirement the settings rule out as `Impossible`
+                .filter(|connection| {
+                    !matches!(connection.requirement, SeedgenRequirement::Impossible)
+                })
+                .map(|connection| {
+                    (
+                        anchor.identifier.as_str(),
+                        logic.nodes[connection.to].identifier(),
+                    )
+                })
+        })
+        .collect::<HashSet<_>>();
+
+    build_graph(areas, locations, |start, end| {
+        in_logic.contains(&(start, end))
+    })
+}
+
+const STATES: &str = ""; // As long as the state data doesn't track coordinates, it isn't useful for our purpose
+
+/// Builds the `Graph` from the full logic, `in_logic` decides whether the connection between two `Node`s is in logic
+fn build_graph(
+    areas: &str,
+    locations: &str,
+    in_logic: impl Fn(&str, &str) -> bool,
+) -> Result<Graph, JsValue> {
     let mut settings = UniverseSettings::default();
     settings.world_settings[0].difficulty = Difficulty::Unsafe; // Ensure no paths are optimized away
-    let logic = wotw_seedgen::logic::parse_logic(areas, locations, states, &settings, false)?;
+    let logic = wotw_seedgen::logic::parse_logic(areas, locations, STATES, &settings, false)?;
 
     let positioned_nodes = logic
         .nodes
@@ -56,78 +374,326 @@ pub fn graph(areas: &str, locations: &str) -> Result<Graph, JsValue> {
         .filter_map(|node| node.map_position().map(|position| (node, position)))
         .collect::<Vec<_>>();
 
-    let nodes = nodes(&positioned_nodes);
-    let connections = connections(&logic.nodes, &positioned_nodes);
+    let definitions = areas::anchors(areas);
+    let node_ranges = node_ranges(&definitions);
+    let nodes = nodes(
+        &logic.nodes,
+        &positioned_nodes,
+        &definitions,
+        &node_ranges,
+        locations,
+    );
+    let connections = connections(&logic.nodes, &positioned_nodes, &definitions, in_logic);
+    let (unpositioned_nodes, dropped_connections) =
+        unpositioned(&logic.nodes, &definitions, &node_ranges);
+    let spatial_index = SpatialIndex::new(&nodes, &connections);
 
-    Ok(Graph { nodes, connections })
+    Ok(Graph {
+        nodes,
+        connections,
+        unpositioned_nodes,
+        dropped_connections,
+        definitions,
+        spatial_index,
+        logic,
+    })
 }
-fn nodes(positioned_nodes: &[(&SeedgenNode, &SeedgenPosition)]) -> __NodeList {
-    let nodes = positioned_nodes
+pub(crate) fn anchors<'a>(
+    nodes: impl IntoIterator<Item = &'a SeedgenNode>,
+) -> impl Iterator<Item = &'a SeedgenAnchor> {
+    nodes.into_iter().filter_map(|node| {
+        if let SeedgenNode::Anchor(anchor) = node {
+            Some(anchor)
+        } else {
+            None
+        }
+    })
+}
+/// Returns the range of the definition of every `Node` by name
+///
+/// Anchors are defined by their `anchor` definition, any other `Node` by the first `pickup`, `state` or `quest` line naming it
+fn node_ranges(definitions: &HashMap<String, AnchorDefinition>) -> HashMap<&str, SourceRange> {
+    let mut ranges = HashMap::<&str, SourceRange>::new();
+    let declarations = definitions
+        .values()
+        .flat_map(|definition| &definition.connections)
+        .filter(|connection| matches!(connection.keyword, "pickup" | "state" | "quest"));
+    for declaration in declarations {
+        let range = ranges
+            .entry(declaration.target.as_str())
+            .or_insert(declaration.range);
+        if declaration.range.start.line < range.start.line {
+            *range = declaration.range;
+        }
+    }
+    ranges.extend(
+        definitions
+            .iter()
+            .map(|(name, definition)| (name.as_str(), definition.range)),
+    );
+    ranges
+}
+fn nodes(
+    logic_nodes: &[SeedgenNode],
+    positioned_nodes: &[(&SeedgenNode, &SeedgenPosition)],
+    definitions: &HashMap<String, AnchorDefinition>,
+    node_ranges: &HashMap<&str, SourceRange>,
+    locations: &str,
+) -> Vec<Node> {
+    let location_data = locations
+        .lines()
+        .filter_map(|line| {
+            let row = line
+                .split(',')
+                .map(|column| column.trim().to_owned())
+                .collect::<Vec<_>>();
+            let identifier = row.first().filter(|identifier| !identifier.is_empty())?;
+            Some((identifier.clone(), row))
+        })
+        .collect::<HashMap<_, _>>();
+
+    positioned_nodes
         .iter()
         .map(|(node, position)| {
             let name = node.identifier().to_owned();
             let position = Vector2::from((*position).clone());
-            Node { name, position }
+            let range = node_ranges.get(name.as_str()).copied();
+            let kind = NodeType::from(node.node_kind());
+            let location_data = match kind {
+                NodeType::Pickup => location_data.get(&name).cloned(),
+                _ => None,
+            };
+            let refills = match node {
+                SeedgenNode::Anchor(anchor) => refills(anchor, logic_nodes, definitions),
+                _ => vec![],
+            };
+            Node {
+                position,
+                range,
+                kind,
+                zone: node.zone().map(Zone::from),
+                uber_state: node.uber_state().map(ToString::to_string),
+                category: location_data
+                    .as_deref()
+                    .map(LocationCategory::from_location_data),
+                location_data,
+                refills,
+                name,
+            }
         })
+        .collect()
+}
+fn refills(
+    anchor: &SeedgenAnchor,
+    nodes: &[SeedgenNode],
+    definitions: &HashMap<String, AnchorDefinition>,
+) -> Vec<Refill> {
+    // seedgen keeps the refills in the order of their lines, which tells apart several refills of the same kind
+    let definitions = definitions
+        .get(&anchor.identifier)
+        .into_iter()
+        .flat_map(|definition| &definition.connections)
+        .filter(|definition| definition.keyword == "refill")
         .collect::<Vec<_>>();
-    __NodeList::from(nodes)
+    anchor
+        .refills
+        .iter()
+        .enumerate()
+        .map(|(index, refill)| {
+            let (kind, amount) = match refill.value {
+                RefillValue::Full => (RefillType::Full, None),
+                RefillValue::Checkpoint => (RefillType::Checkpoint, None),
+                RefillValue::Health(amount) => (RefillType::Health, Some(amount)),
+                RefillValue::Energy(amount) => (RefillType::Energy, Some(amount)),
+            };
+            let range = definitions
+                .get(index)
+                .filter(|definition| definition.target == refill_name(&refill.value))
+                .map(|definition| definition.range);
+            Refill {
+                kind,
+                amount,
+                requirement: Requirement::from_seedgen(&refill.requirement, nodes),
+                range,
+            }
+        })
+        .collect()
 }
 fn connections(
     nodes: &[SeedgenNode],
     positioned_nodes: &[(&SeedgenNode, &SeedgenPosition)],
-) -> __ConnectionList {
-    let mut node_pairs = positioned_nodes
-        .iter()
-        .filter_map(|(node, _)| {
-            if let SeedgenNode::Anchor(anchor) = node {
-                Some(anchor)
-            } else {
-                None
-            }
-        }) // Anchors
+    definitions: &HashMap<String, AnchorDefinition>,
+    in_logic: impl Fn(&str, &str) -> bool,
+) -> Vec<Connection> {
+    let mut node_pairs = anchors(positioned_nodes.iter().map(|(node, _)| *node))
         .flat_map(|anchor| {
             anchor
                 .connections
                 .iter()
                 .filter(|connection| !connection.implicitly_generated)
-                .map(|connection| &nodes[connection.to]) // Target nodes
-                .filter(|node| node.position().is_some()) // Only positioned targets
-                .map(|target| (anchor, target)) // Pairs of anchors and target nodes
+                .map(|connection| (connection, &nodes[connection.to])) // Target nodes
+                .filter(|(_, node)| node.position().is_some()) // Only positioned targets
+                .map(|(connection, target)| (anchor, connection, target)) // Anchors with their connections and target nodes
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
 
     let mut connections = Vec::with_capacity(node_pairs.len());
-    while let Some((start, end)) = node_pairs.pop() {
-        let unidirectional =
+    while let Some((start, connection, end)) = node_pairs.pop() {
+        let inverse_connection =
             match node_pairs
                 .iter()
                 .enumerate()
-                .find(|(_, (other_start, other_end))| {
+                .find(|(_, (other_start, _, other_end))| {
                     start.index == other_end.index() && end.index() == other_start.index
                 }) {
                 Some((reverse_connection_index, _)) => {
-                    node_pairs.remove(reverse_connection_index);
-                    false
+                    let (_, inverse_connection, _) = node_pairs.remove(reverse_connection_index);
+                    Some(inverse_connection)
                 }
-                None => true,
+                None => None,
             };
-        let kind = match end.node_kind() {
-            NodeKind::Anchor => ConnectionType::Branch,
-            _ => ConnectionType::Leaf,
-        };
+        let unidirectional = inverse_connection.is_none();
+        let requirement = Requirement::from_seedgen(&connection.requirement, nodes);
+        let inverse_requirement =
+            inverse_connection.map(|inverse_connection: &SeedgenConnection| {
+                Requirement::from_seedgen(&inverse_connection.requirement, nodes)
+            });
+        let end_kind = end.node_kind();
         let start = start.identifier.clone();
         let end = end.identifier().to_owned();
+        let definition = connection_definition(definitions, &start, &end);
+        let inverse_definition = if unidirectional {
+            None
+        } else {
+            connection_definition(definitions, &end, &start)
+        };
+        let declared_needs = |predicate: &dyn Fn(&SeedgenRequirement) -> bool| {
+            needs(&connection.requirement, predicate)
+                || inverse_connection.is_some_and(|inverse_connection| {
+                    needs(&inverse_connection.requirement, predicate)
+                })
+        };
+        let kind = match end_kind {
+            NodeKind::Anchor
+                if declared_needs(&|requirement| {
+                    matches!(requirement, SeedgenRequirement::Teleporter(_))
+                }) =>
+            {
+                ConnectionType::Teleporter
+            }
+            NodeKind::Anchor
+                if declared_needs(&|requirement| {
+                    matches!(
+                        requirement,
+                        SeedgenRequirement::Resource(Resource::Keystone, _)
+                    )
+                }) =>
+            {
+                ConnectionType::Door
+            }
+            NodeKind::Anchor => ConnectionType::Branch,
+            // World states have no map position, so their connections never get here
+            NodeKind::Pickup | NodeKind::Quest | NodeKind::State => ConnectionType::Leaf,
+        };
         let connection = Connection {
+            in_logic: in_logic(&start, &end),
+            inverse_in_logic: !unidirectional && in_logic(&end, &start),
             start,
             end,
             unidirectional,
             kind,
+            range: definition.map(|definition| definition.range),
+            inverse_range: inverse_definition.map(|definition| definition.range),
+            requirement: Some(requirement),
+            inverse_requirement,
         };
         connections.push(connection);
     }
 
-    __ConnectionList::from(connections)
+    connections
+}
+/// Returns `true` if every way to meet `requirement` includes a requirement matching `predicate`
+fn needs(
+    requirement: &SeedgenRequirement,
+    predicate: &dyn Fn(&SeedgenRequirement) -> bool,
+) -> bool {
+    predicate(requirement)
+        || match requirement {
+            SeedgenRequirement::And(requirements) => requirements
+                .iter()
+                .any(|requirement| needs(requirement, predicate)),
+            SeedgenRequirement::Or(requirements) => {
+                !requirements.is_empty()
+                    && requirements
+                        .iter()
+                        .all(|requirement| needs(requirement, predicate))
+            }
+            _ => false,
+        }
+}
+/// The name following `refill` in `areas.wotw` for a kind of refill
+pub(crate) fn refill_name(value: &RefillValue) -> &'static str {
+    match value {
+        RefillValue::Full => "Full",
+        RefillValue::Checkpoint => "Checkpoint",
+        RefillValue::Health(_) => "Health",
+        RefillValue::Energy(_) => "Energy",
+    }
+}
+fn unpositioned(
+    nodes: &[SeedgenNode],
+    definitions: &HashMap<String, AnchorDefinition>,
+    node_ranges: &HashMap<&str, SourceRange>,
+) -> (Vec<UnpositionedNode>, Vec<DroppedConnection>) {
+    let unpositioned_nodes = nodes
+        .iter()
+        .filter(|node| node.map_position().is_none() && has_map_position(node))
+        .map(|node| {
+            let name = node.identifier().to_owned();
+            let range = node_ranges.get(name.as_str()).copied();
+            UnpositionedNode { name, range }
+        })
+        .collect::<Vec<_>>();
+
+    let dropped_connections = anchors(nodes)
+        .flat_map(|anchor| {
+            anchor
+                .connections
+                .iter()
+                .filter(|connection| !connection.implicitly_generated)
+                .map(|connection| &nodes[connection.to])
+                .filter(|target| has_map_position(target))
+                .filter(|target| {
+                    nodes[anchor.index].map_position().is_none() || target.position().is_none()
+                })
+                .map(|target| {
+                    let start = anchor.identifier.clone();
+                    let end = target.identifier().to_owned();
+                    let range = connection_definition(definitions, &start, &end)
+                        .map(|definition| definition.range);
+                    DroppedConnection { start, end, range }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    (unpositioned_nodes, dropped_connections)
+}
+/// Returns `false` for world states, which never have a map position and would drown out the `Node`s missing one
+fn has_map_position(node: &SeedgenNode) -> bool {
+    !matches!(node.node_kind(), NodeKind::State)
+}
+fn connection_definition<'a>(
+    definitions: &'a HashMap<String, AnchorDefinition>,
+    from: &str,
+    to: &str,
+) -> Option<&'a ConnectionDefinition> {
+    definitions
+        .get(from)?
+        .connections
+        .iter()
+        .find(|connection| connection.target == to)
 }
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -158,16 +724,155 @@ pub struct Node {
     pub name: String,
     /// The map position of this `Node`, using in-game coordinates
     pub position: Vector2,
+    range: Option<SourceRange>,
+    kind: NodeType,
+    zone: Option<Zone>,
+    #[wasm_bindgen(getter_with_clone)]
+    /// The uber state of this `Node` as `group|id`
+    ///
+    /// `undefined` if this `Node` has no uber state, e.g. for anchors
+    pub uber_state: Option<String>,
+    category: Option<LocationCategory>,
+    #[wasm_bindgen(getter_with_clone)]
+    /// The columns of this `Node`'s row in the locations data
+    ///
+    /// `undefined` if this `Node` is not a pickup or missing from the locations data
+    pub location_data: Option<Vec<String>>,
+    refills: Vec<Refill>,
 }
 #[wasm_bindgen]
 impl Node {
+    #[wasm_bindgen(getter, js_name = "type")]
+    /// The `NodeType` of this `Node`
+    pub fn kind(&self) -> NodeTypeEnum {
+        self.kind.into_js_enum()
+    }
+    #[wasm_bindgen(getter)]
+    /// The `Zone` this `Node` is in
+    ///
+    /// `undefined` if the logic doesn't assign a zone to this `Node`
+    pub fn zone(&self) -> Option<ZoneEnum> {
+        self.zone.map(Zone::into_js_enum)
+    }
+    #[wasm_bindgen(getter)]
+    /// The `LocationCategory` of this `Node`, based on its row in the locations data
+    ///
+    /// `undefined` if this `Node` is not a pickup or missing from the locations data
+    pub fn category(&self) -> Option<LocationCategoryEnum> {
+        self.category.map(LocationCategory::into_js_enum)
+    }
+    #[wasm_bindgen(getter)]
+    /// The `Refill`s declared by `refill` lines inside this `Node`'s `anchor` definition
+    ///
+    /// Empty if this `Node` is not an anchor
+    pub fn refills(&self) -> RefillArray {
+        __RefillList::from(self.refills.clone()).into_js_array()
+    }
+    #[wasm_bindgen]
+    /// The range of this `Node` definition in the source file, including everything nested inside it
+    ///
+    /// For anchors this is the `anchor` definition, for other `Node`s the first `pickup`, `state` or `quest` line declaring them
+    ///
+    /// `undefined` if the definition could not be found in the source file
+    pub fn source_location(&self) -> Option<SourceRange> {
+        self.range
+    }
+}
+
+wrapper_list! {
     #[wasm_bindgen]
-    /// The location of this `Node` definition in the source file
-    pub fn source_location(&self, areas: &str) -> Option<SourceLocation> {
-        Regex::new(&format!("^anchor +{}", self.name))
-            .unwrap()
-            .find(areas)
-            .map(|matched| SourceLocation::from_index(areas, matched.start()))
+    #[derive(Clone)]
+    pub struct __RefillList {
+        inner: IntoIter<Refill>,
+    }
+}
+
+#[wasm_bindgen]
+/// A `refill` line inside an anchor, see `Node.refills`
+#[derive(Clone)]
+pub struct Refill {
+    kind: RefillType,
+    /// The number of health plants for `Health` refills and the energy restored for `Energy` refills
+    ///
+    /// `undefined` for `Full` and `Checkpoint` refills
+    pub amount: Option<f32>,
+    #[wasm_bindgen(getter_with_clone)]
+    /// The `Requirement` to use this `Refill`
+    pub requirement: Requirement,
+    range: Option<SourceRange>,
+}
+#[wasm_bindgen]
+impl Refill {
+    #[wasm_bindgen(getter, js_name = "type")]
+    /// The `RefillType` of this `Refill`
+    pub fn kind(&self) -> RefillTypeEnum {
+        self.kind.into_js_enum()
+    }
+    #[wasm_bindgen]
+    /// The range of this `refill` line in the source file, including its requirement
+    ///
+    /// `undefined` if the definition could not be found in the source file
+    pub fn source_location(&self) -> Option<SourceRange> {
+        self.range
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __UnpositionedNodeList {
+        inner: IntoIter<UnpositionedNode>,
+    }
+}
+
+#[wasm_bindgen]
+/// Node from the logic without a map position, see `Graph.unpositioned_nodes`
+#[derive(Clone)]
+pub struct UnpositionedNode {
+    #[wasm_bindgen(getter_with_clone)]
+    /// The name of this node
+    pub name: String,
+    range: Option<SourceRange>,
+}
+#[wasm_bindgen]
+impl UnpositionedNode {
+    #[wasm_bindgen]
+    /// The range of this node's definition in the source file, see `Node.source_location`
+    ///
+    /// `undefined` if the definition could not be found in the source file
+    pub fn source_location(&self) -> Option<SourceRange> {
+        self.range
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __DroppedConnectionList {
+        inner: IntoIter<DroppedConnection>,
+    }
+}
+
+#[wasm_bindgen]
+/// Connection from the logic leading from or to a node without a map position, see `Graph.dropped_connections`
+#[derive(Clone)]
+pub struct DroppedConnection {
+    #[wasm_bindgen(getter_with_clone)]
+    /// Name of the node this connection starts at
+    pub start: String,
+    #[wasm_bindgen(getter_with_clone)]
+    /// Name of the node this connection ends at
+    pub end: String,
+    range: Option<SourceRange>,
+}
+#[wasm_bindgen]
+impl DroppedConnection {
+    #[wasm_bindgen]
+    /// The range of this connection's definition in the source file, including its requirement
+    ///
+    /// `undefined` if the definition could not be found in the source file
+    pub fn source_location(&self) -> Option<SourceRange> {
+        self.range
     }
 }
 
@@ -191,7 +896,13 @@ pub struct Connection {
     pub end: String,
     /// `true` if this `Connection` only leads from `start` to `end`, `false` if this connection goes in both directions
     pub unidirectional: bool,
-    kind: ConnectionType,
+    pub(crate) kind: ConnectionType,
+    range: Option<SourceRange>,
+    inverse_range: Option<SourceRange>,
+    requirement: Option<Requirement>,
+    inverse_requirement: Option<Requirement>,
+    in_logic: bool,
+    inverse_in_logic: bool,
 }
 #[wasm_bindgen]
 impl Connection {
@@ -201,52 +912,69 @@ impl Connection {
         self.kind.into_js_enum()
     }
     #[wasm_bindgen]
-    /// The location of this `Connection` definition in the source file
+    /// The `Requirement` to use this `Connection`, as compiled by seedgen with macros and regions expanded
+    ///
+    /// If this `Connection` is not `unidirectional`, setting `inverse` to `false` will return the requirement from `start` to `end`,
+    /// while setting `inverse` to `true` will return the requirement from `end` to `start`
+    ///
+    /// `undefined` for the `inverse` of a `unidirectional` `Connection`
+    pub fn requirement(&self, inverse: bool) -> Option<Requirement> {
+        if inverse {
+            self.inverse_requirement.clone()
+        } else {
+            self.requirement.clone()
+        }
+    }
+    #[wasm_bindgen]
+    /// Whether this `Connection` can be used under the `WorldSettings` passed to `logic_graph`
+    ///
+    /// If this `Connection` is not `unidirectional`, setting `inverse` to `false` will check the direction from `start` to `end`,
+    /// while setting `inverse` to `true` will check the direction from `end` to `start`
+    ///
+    /// Always `true` for `Graph`s returned by `graph`, except for the `inverse` direction of `unidirectional` `Connection`s
+    pub fn in_logic(&self, inverse: bool) -> bool {
+        if inverse {
+            self.inverse_in_logic
+        } else {
+            self.in_logic
+        }
+    }
+    #[wasm_bindgen]
+    /// The range of this `Connection` definition in the source file, including its requirement
     ///
     /// If this `Connection` is not `unidirectional`, setting `inverse` to `false` will return the definition from `start` to `end`,
     /// while setting `inverse` to `true` will return the definition from `end` to `start`
-    pub fn source_location(&self, areas: &str, inverse: bool) -> Option<SourceLocation> {
-        let (from, to) = if inverse {
-            (&self.end, &self.start)
+    pub fn source_location(&self, inverse: bool) -> Option<SourceRange> {
+        if inverse {
+            self.inverse_range
         } else {
-            (&self.start, &self.end)
-        };
-
-        let connection_pattern = match self.kind {
-            ConnectionType::Branch => format!("^ +conn +{to}"),
-            ConnectionType::Leaf => format!("^ +(?:pickup|quest) +{to}"),
-        };
-
-        let index = Regex::new(&connection_pattern)
-            .unwrap()
-            .find_iter(areas)
-            .find_map(|matched| {
-                let anchor = areas[..matched.start()].rfind("\nanchor")?;
-                if areas[anchor + 7..]
-                    .trim_start_matches(' ')
-                    .starts_with(from)
-                {
-                    Some(matched.start())
-                } else {
-                    None
-                }
-            })?;
-
-        Some(SourceLocation::from_index(areas, index))
+            self.range
+        }
     }
 }
 #[wasm_bindgen]
 /// line and character location inside a text file
+#[derive(Clone, Copy)]
 pub struct SourceLocation {
+    /// Zero-based index of the line
     pub line: usize,
+    /// Zero-based column inside the line, in UTF-16 code units
     pub char: usize,
 }
-impl SourceLocation {
-    fn from_index(source: &str, index: usize) -> Self {
-        let line = source[..index].bytes().filter(|b| *b == b'\n').count();
-        let char = source[index..].find(|c| c != ' ').unwrap_or(0);
 
-        Self { line, char }
+#[wasm_bindgen]
+/// Range between two `SourceLocation`s inside a text file
+#[derive(Clone, Copy)]
+pub struct SourceRange {
+    /// Location of the first character in the range
+    pub start: SourceLocation,
+    /// Location after the last character in the range
+    pub end: SourceLocation,
+}
+impl SourceRange {
+    fn contains(&self, location: SourceLocation) -> bool {
+        let location = (location.line, location.char);
+        (self.start.line, self.start.char) <= location && location <= (self.end.line, self.end.char)
     }
 }
 
@@ -0,0 +1,891 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Add;
+
+use wasm_bindgen::prelude::*;
+
+use wotw_seedgen::item::Item;
+use wotw_seedgen::settings::WorldSettings as SeedgenWorldSettings;
+use wotw_seedgen::util::Orbs;
+use wotw_seedgen::world::graph::{
+    Anchor as SeedgenAnchor, Node as SeedgenNode, RefillValue, Requirement as SeedgenRequirement,
+};
+use wotw_seedgen::world::player::{Inventory as SeedgenInventory, Player};
+use wotw_seedgen::world::World;
+
+use wasm_bindgen_helper_macros::*;
+
+use crate::map::{self, Graph};
+use crate::requirement::Requirement;
+use crate::settings::WorldSettings;
+
+/// A set of items to check requirements against, see `Graph.reachable`
+#[wasm_bindgen]
+pub struct Inventory {
+    inventory: SeedgenInventory,
+    states: Vec<String>,
+}
+#[wasm_bindgen]
+impl Inventory {
+    /// Creates a new `Inventory`
+    ///
+    /// Every entry of `items` is either an item as seedgen names it (e.g. `"Dash"` or `"TripleJump"`),
+    /// an item with an amount (e.g. `"Keystone=4"`) or the name of a world state (e.g. `"MarshSpawn.Lever"`).
+    /// Repeated entries add up
+    ///
+    /// The health and energy of a new game are always included
+    ///
+    /// @throws {string} if an item or amount fails to parse
+    #[wasm_bindgen(constructor)]
+    pub fn new(items: Vec<String>) -> Result<Inventory, String> {
+        let mut inventory = SeedgenInventory::default();
+        let mut states = vec![];
+        for entry in &items {
+            let entry = entry.trim();
+            if entry.contains('.') {
+                states.push(entry.to_owned());
+                continue;
+            }
+            let (item, amount) = match entry.split_once('=') {
+                Some((item, amount)) => {
+                    let amount = amount
+                        .trim()
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid amount in \"{entry}\""))?;
+                    (item.trim(), amount)
+                }
+                None => (entry, 1),
+            };
+            let item = item
+                .parse::<Item>()
+                .map_err(|err| format!("invalid item \"{item}\": {err}"))?;
+            inventory.grant(item, amount);
+        }
+        Ok(Self { inventory, states })
+    }
+}
+impl Inventory {
+    /// A seedgen `Player` holding the items of this `Inventory`
+    fn player<'a>(&self, settings: &'a SeedgenWorldSettings) -> Player<'a> {
+        let mut player = Player::new(settings);
+        player.inventory = self.inventory.clone();
+        player
+    }
+    /// The indices of the world states in this `Inventory`
+    fn states(&self, nodes: &[SeedgenNode]) -> Result<Vec<usize>, String> {
+        self.states
+            .iter()
+            .map(|state| {
+                nodes
+                    .iter()
+                    .find(|node| node.identifier() == state)
+                    .map(SeedgenNode::index)
+                    .ok_or_else(|| format!("no world state named \"{state}\""))
+            })
+            .collect()
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __BlockedConnectionList {
+        inner: IntoIter<BlockedConnection>,
+    }
+}
+
+/// The result of `Graph.reachable`
+#[wasm_bindgen]
+pub struct Reachability {
+    /// Names of every `Node` that can be reached, including the spawn
+    #[wasm_bindgen(getter_with_clone)]
+    pub reachable: Vec<String>,
+    blocked: Vec<BlockedConnection>,
+}
+#[wasm_bindgen]
+impl Reachability {
+    /// Connections leading from a reachable `Node` to an unreachable one which are only blocked by missing items
+    ///
+    /// Connections that are out of logic for the difficulty or tricks are not included
+    #[wasm_bindgen(getter)]
+    pub fn blocked(&self) -> BlockedConnectionArray {
+        __BlockedConnectionList::from(self.blocked.clone()).into_js_array()
+    }
+}
+
+/// A connection that could be used with more items, see `Reachability.blocked`
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct BlockedConnection {
+    /// Name of the reachable `Node` this connection starts at
+    #[wasm_bindgen(getter_with_clone)]
+    pub start: String,
+    /// Name of the unreachable `Node` this connection ends at
+    #[wasm_bindgen(getter_with_clone)]
+    pub end: String,
+    /// The full requirement of the connection
+    #[wasm_bindgen(getter_with_clone)]
+    pub requirement: Requirement,
+    missing: Vec<ItemSet>,
+}
+#[wasm_bindgen]
+impl BlockedConnection {
+    /// The sets of items which would each make this connection usable when added to the inventory, as computed by seedgen
+    #[wasm_bindgen(getter)]
+    pub fn missing(&self) -> ItemSetArray {
+        __ItemSetList::from(self.missing.clone()).into_js_array()
+    }
+}
+
+#[wasm_bindgen]
+impl Graph {
+    #[wasm_bindgen]
+    /// Returns which `Node`s can be reached from the anchor named `spawn` using the items in `inventory`
+    ///
+    /// This uses seedgen's reach check, so requirements are checked against the difficulty and tricks in `world_settings`
+    /// and health and energy are used up and refilled the same way as during seed generation.
+    /// World states like `MarshSpawn.Lever` count as met once they are reached or if they are in the `inventory`
+    ///
+    /// Unlike the `nodes` of the `Graph`, this includes `Node`s without a map position
+    ///
+    /// @throws {string} if seedgen can't spawn on the anchor named `spawn` or the `inventory` names an unknown world state
+    pub fn reachable(
+        &self,
+        inventory: &Inventory,
+        world_settings: &WorldSettings,
+        spawn: &str,
+    ) -> Result<Reachability, String> {
+        let reached = self.reach(inventory, &world_settings.0, spawn)?;
+        let blocked = self.blocked(&reached, inventory, &world_settings.0);
+        let mut reachable = reached
+            .into_iter()
+            .map(|index| self.logic.nodes[index].identifier().to_owned())
+            .collect::<Vec<_>>();
+        reachable.sort();
+
+        Ok(Reachability { reachable, blocked })
+    }
+    #[wasm_bindgen]
+    /// Returns the cheapest way from the anchor named `from` to the `Node` named `to` using the items in `inventory`
+    ///
+    /// Requirements are checked by seedgen the same way as in `reachable`, world states count as met if they are reachable from `from`.
+    /// The path starts with full health and energy, every connection uses up the health and energy seedgen determines for its requirement
+    /// and refills along the way restore them. The cost of a path is the energy used plus a tenth of the health used
+    ///
+    /// @throws {string} if there is no `Node` named `to`, seedgen can't spawn on the anchor named `from` or the `inventory` names an unknown world state
+    pub fn path(
+        &self,
+        from: &str,
+        to: &str,
+        inventory: &Inventory,
+        world_settings: &WorldSettings,
+    ) -> Result<Path, String> {
+        let nodes = &self.logic.nodes;
+        let to = nodes
+            .iter()
+            .position(|node| node.identifier() == to)
+            .ok_or_else(|| format!("no node named \"{to}\""))?;
+        let settings = &world_settings.0;
+        let reached = self.reach(inventory, settings, from)?;
+        let spawn = self.logic.find_spawn(from)?.index();
+        let player = inventory.player(settings);
+
+        let found = if reached.contains(&to) {
+            cheapest_path(spawn, to, player.max_orbs(), |current, orbs| {
+                let SeedgenNode::Anchor(anchor) = &nodes[current] else {
+                    return vec![];
+                };
+                let orbs = refill(anchor, &player, &reached, orbs);
+                anchor
+                    .connections
+                    .iter()
+                    .filter(|connection| !connection.implicitly_generated)
+                    .flat_map(|connection| {
+                        // seedgen returns the orbs left after every way to meet the requirement
+                        connection
+                            .requirement
+                            .is_met(&player, &reached, orbs)
+                            .into_iter()
+                            .map(move |left| (connection, left))
+                    })
+                    .map(|(connection, left)| {
+                        let cost = Cost {
+                            energy: (orbs.energy - left.energy).max(0.),
+                            health: (orbs.health - left.health).max(0.),
+                        };
+                        let step = PathStep {
+                            start: anchor.identifier.clone(),
+                            end: nodes[connection.to].identifier().to_owned(),
+                            requirement: Requirement::from_seedgen(&connection.requirement, nodes),
+                            energy: cost.energy,
+                            health: cost.health,
+                        };
+                        (connection.to, cost, left, step)
+                    })
+                    .collect()
+            })
+        } else {
+            None
+        };
+
+        Ok(match found {
+            Some(steps) => {
+                let energy = steps.iter().map(|step| step.energy).sum();
+                let health = steps.iter().map(|step| step.health).sum();
+                Path {
+                    found: true,
+                    steps,
+                    energy,
+                    health,
+                    missing: vec![],
+                }
+            }
+            None => {
+                let missing = self
+                    .item_alternatives(spawn, &world_settings.0)
+                    .remove(&to)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|items| without(items, &inventory.inventory))
+                    .filter(|items| items.items.values().any(|amount| *amount > 0))
+                    .collect::<Vec<_>>();
+                Path {
+                    found: false,
+                    steps: vec![],
+                    energy: 0.,
+                    health: 0.,
+                    missing: minimize(missing).iter().map(ItemSet::from).collect(),
+                }
+            }
+        })
+    }
+    #[wasm_bindgen]
+    /// Returns the minimal sets of items that make the `Node` named `target` reachable from the anchor named `spawn`
+    ///
+    /// Each `ItemSet` is one alternative, none of them contains another.
+    /// seedgen computes the items needed for every connection using the difficulty and tricks in `world_settings`,
+    /// world states needed along the way add the items needed to reach them.
+    /// Items needed by several connections are combined by keeping the highest amount
+    ///
+    /// To keep the result manageable, at most 16 alternatives with the fewest items are kept for every `Node`
+    ///
+    /// Empty if `target` can't be reached with any items
+    ///
+    /// @throws {string} if seedgen can't spawn on the anchor named `spawn`
+    pub fn required_items(
+        &self,
+        target: &str,
+        world_settings: &WorldSettings,
+        spawn: &str,
+    ) -> Result<ItemSetArray, String> {
+        let nodes = &self.logic.nodes;
+        let spawn = self.logic.find_spawn(spawn)?.index();
+        let mut alternatives = self.item_alternatives(spawn, &world_settings.0);
+
+        let item_sets = nodes
+            .iter()
+            .position(|node| node.identifier() == target)
+            .and_then(|target| alternatives.remove(&target))
+            .unwrap_or_default()
+            .iter()
+            .map(ItemSet::from)
+            .collect::<Vec<_>>();
+        Ok(__ItemSetList::from(item_sets).into_js_array())
+    }
+}
+impl Graph {
+    /// Returns the minimal sets of items needed to reach every `Node` from the `Node` with the index `spawn`, by `Node` index
+    ///
+    /// See `Graph.required_items`
+    fn item_alternatives(
+        &self,
+        spawn: usize,
+        settings: &SeedgenWorldSettings,
+    ) -> HashMap<usize, Vec<SeedgenInventory>> {
+        let nodes = &self.logic.nodes;
+        let player = Player::new(settings);
+        let no_states = HashSet::new();
+
+        let mut dependents: HashMap<usize, Vec<usize>> = HashMap::new();
+        let connections = map::anchors(nodes)
+            .map(|anchor| {
+                let connections = anchor
+                    .connections
+                    .iter()
+                    .filter(|connection| !connection.implicitly_generated)
+                    .map(|connection| {
+                        let needed = Needed::new(&connection.requirement, &player, &no_states);
+                        needed.for_each_state(&mut |state| {
+                            dependents.entry(state).or_default().push(anchor.index)
+                        });
+                        (connection.to, needed)
+                    })
+                    .collect::<Vec<_>>();
+                (anchor.index, connections)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut alternatives: HashMap<usize, Vec<SeedgenInventory>> =
+            HashMap::from([(spawn, vec![SeedgenInventory::default()])]);
+        let mut queue = VecDeque::from([spawn]);
+        while let Some(anchor) = queue.pop_front() {
+            let Some(anchor_connections) = connections.get(&anchor) else {
+                continue;
+            };
+            let start = alternatives.get(&anchor).cloned().unwrap_or_default();
+            for (target, needed) in anchor_connections {
+                let candidates = combine(&start, &needed.alternatives(&alternatives));
+                let previous = alternatives.get(target).cloned().unwrap_or_default();
+                let next = minimize(previous.iter().cloned().chain(candidates).collect());
+                let changed = next
+                    .iter()
+                    .any(|items| !previous.iter().any(|other| contains(items, other)));
+                if changed {
+                    alternatives.insert(*target, next);
+                    queue.push_back(*target);
+                    queue.extend(dependents.get(target).into_iter().flatten());
+                }
+            }
+        }
+
+        alternatives
+    }
+    /// Returns the indices of every `Node` in the logic that seedgen's reach check finds from `spawn`
+    fn reach(
+        &self,
+        inventory: &Inventory,
+        settings: &SeedgenWorldSettings,
+        spawn: &str,
+    ) -> Result<HashSet<usize>, String> {
+        let mut world = World::new(&self.logic, settings);
+        world.player = inventory.player(settings);
+        let sets = inventory.states(&self.logic.nodes)?;
+        let spawn = self.logic.find_spawn(spawn)?;
+
+        let reached =
+            self.logic
+                .reached_locations(&world.player, spawn, &world.uber_states, &sets)?;
+        let mut reached = reached
+            .reached
+            .into_iter()
+            .map(SeedgenNode::index)
+            .collect::<HashSet<_>>();
+        reached.insert(spawn.index());
+        reached.extend(sets);
+        Ok(reached)
+    }
+    /// Returns the connections leading away from `reached` which are only blocked by missing items
+    fn blocked(
+        &self,
+        reached: &HashSet<usize>,
+        inventory: &Inventory,
+        settings: &SeedgenWorldSettings,
+    ) -> Vec<BlockedConnection> {
+        let nodes = &self.logic.nodes;
+        let player = inventory.player(settings);
+        let mut blocked = map::anchors(nodes)
+            .filter(|anchor| reached.contains(&anchor.index))
+            .flat_map(|anchor| {
+                anchor
+                    .connections
+                    .iter()
+                    .filter(|connection| !connection.implicitly_generated)
+                    .map(move |connection| (anchor, connection))
+            })
+            .filter(|(_, connection)| !reached.contains(&connection.to))
+            .filter_map(|(anchor, connection)| {
+                let missing = connection
+                    .requirement
+                    .items_needed(&player, reached)
+                    .into_iter()
+                    .map(|(items, _)| ItemSet::from(&items))
+                    .collect::<Vec<_>>();
+                (!missing.is_empty()).then(|| BlockedConnection {
+                    start: anchor.identifier.clone(),
+                    end: nodes[connection.to].identifier().to_owned(),
+                    requirement: Requirement::from_seedgen(&connection.requirement, nodes),
+                    missing,
+                })
+            })
+            .collect::<Vec<_>>();
+        blocked.sort_by(|a, b| (&a.start, &a.end).cmp(&(&b.start, &b.end)));
+        blocked
+    }
+}
+
+/// The result of `Graph.path`
+#[wasm_bindgen]
+pub struct Path {
+    /// `true` if `to` can be reached from `from`
+    pub found: bool,
+    steps: Vec<PathStep>,
+    /// Total energy used along the path
+    pub energy: f32,
+    /// Total health used along the path
+    pub health: f32,
+    missing: Vec<ItemSet>,
+}
+#[wasm_bindgen]
+impl Path {
+    /// The connections to use in order, empty if no path was `found`
+    #[wasm_bindgen(getter)]
+    pub fn steps(&self) -> PathStepArray {
+        __PathStepList::from(self.steps.clone()).into_js_array()
+    }
+    /// If no path was `found`, the sets of items which would each make `to` reachable when added to the inventory, see `Graph.required_items`
+    ///
+    /// Empty if a path was `found`, if `to` can't be reached with any items
+    /// or if the inventory has the items but no way with enough health and energy exists
+    #[wasm_bindgen(getter)]
+    pub fn missing(&self) -> ItemSetArray {
+        __ItemSetList::from(self.missing.clone()).into_js_array()
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __PathStepList {
+        inner: IntoIter<PathStep>,
+    }
+}
+
+/// One connection along a `Path`
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct PathStep {
+    /// Name of the `Node` this step starts at
+    #[wasm_bindgen(getter_with_clone)]
+    pub start: String,
+    /// Name of the `Node` this step ends at
+    #[wasm_bindgen(getter_with_clone)]
+    pub end: String,
+    /// The full requirement of the connection
+    #[wasm_bindgen(getter_with_clone)]
+    pub requirement: Requirement,
+    /// Energy used up by this step, as determined by seedgen
+    pub energy: f32,
+    /// Health used up by this step, as determined by seedgen
+    pub health: f32,
+}
+
+/// One way to a `Node` found by `cheapest_path`
+struct Label<T> {
+    node: usize,
+    cost: Cost,
+    orbs: Orbs,
+    /// The index of the `Label` this way continues and the step taken from there
+    previous: Option<(usize, T)>,
+}
+impl<T> Label<T> {
+    /// Returns `true` if this way to the same `Node` is at least as cheap and leaves at least as much health and energy as `other`
+    fn dominates(&self, other: &Label<T>) -> bool {
+        self.cost.total() <= other.cost.total()
+            && self.orbs.health >= other.orbs.health
+            && self.orbs.energy >= other.orbs.energy
+    }
+}
+
+/// Returns the steps of the cheapest way from `from` to `to`, or `None` if there is none
+///
+/// `steps` returns the connections of a `Node` usable when arriving with the given orbs,
+/// each with its target, its cost, the orbs left afterwards and the step itself.
+/// Every way to a `Node` is kept unless another way is at least as cheap and leaves at least as much health and energy,
+/// so a cheap way running out of health or energy doesn't hide a more expensive way that can continue
+fn cheapest_path<T: Clone>(
+    from: usize,
+    to: usize,
+    orbs: Orbs,
+    steps: impl Fn(usize, Orbs) -> Vec<(usize, Cost, Orbs, T)>,
+) -> Option<Vec<T>> {
+    let mut labels: Vec<Label<T>> = vec![Label {
+        node: from,
+        cost: Cost::default(),
+        orbs,
+        previous: None,
+    }];
+    let mut by_node: HashMap<usize, Vec<usize>> = HashMap::from([(from, vec![0])]);
+    // Labels which were expanded or beaten by another label
+    let mut closed = HashSet::new();
+
+    // The number of labels stays small enough to find the cheapest open one by searching all of them
+    while let Some(current) = (0..labels.len())
+        .filter(|index| !closed.contains(index))
+        .min_by(|a, b| labels[*a].cost.total().total_cmp(&labels[*b].cost.total()))
+    {
+        closed.insert(current);
+        let Label {
+            node, cost, orbs, ..
+        } = labels[current];
+        if node == to {
+            let mut path = vec![];
+            let mut label = current;
+            while let Some((previous, step)) = &labels[label].previous {
+                path.push(step.clone());
+                label = *previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for (target, step_cost, left, step) in steps(node, orbs) {
+            let label = Label {
+                node: target,
+                cost: cost + step_cost,
+                orbs: left,
+                previous: Some((current, step)),
+            };
+            let existing = by_node.entry(target).or_default();
+            if existing
+                .iter()
+                .any(|other| labels[*other].dominates(&label))
+            {
+                continue;
+            }
+            // Beaten labels stay in `labels` because other labels may continue them
+            existing.retain(|other| {
+                let beaten = label.dominates(&labels[*other]);
+                if beaten {
+                    closed.insert(*other);
+                }
+                !beaten
+            });
+            existing.push(labels.len());
+            labels.push(label);
+        }
+    }
+
+    None
+}
+
+#[derive(Clone, Copy, Default)]
+struct Cost {
+    energy: f32,
+    health: f32,
+}
+impl Cost {
+    fn total(self) -> f32 {
+        self.energy + self.health / 10.
+    }
+}
+impl Add for Cost {
+    type Output = Cost;
+    fn add(self, other: Cost) -> Cost {
+        Cost {
+            energy: self.energy + other.energy,
+            health: self.health + other.health,
+        }
+    }
+}
+
+/// Applies the refills of `anchor` whose requirement is met to `orbs`
+fn refill(anchor: &SeedgenAnchor, player: &Player, states: &HashSet<usize>, orbs: Orbs) -> Orbs {
+    let max = player.max_orbs();
+    let mut orbs = orbs;
+    for refill in &anchor.refills {
+        if refill.requirement.is_met(player, states, orbs).is_empty() {
+            continue;
+        }
+        match refill.value {
+            RefillValue::Full => orbs = max,
+            RefillValue::Checkpoint => {
+                let checkpoint = player.checkpoint_orbs();
+                orbs.health = orbs.health.max(checkpoint.health);
+                orbs.energy = orbs.energy.max(checkpoint.energy);
+            }
+            RefillValue::Health(amount) => orbs.health += amount * player.health_plant_drops(),
+            RefillValue::Energy(amount) => orbs.energy += amount,
+        }
+    }
+    Orbs {
+        health: orbs.health.min(max.health),
+        energy: orbs.energy.min(max.energy),
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __ItemSetList {
+        inner: IntoIter<ItemSet>,
+    }
+}
+
+/// One alternative set of items, see `Graph.required_items`
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct ItemSet {
+    /// The items in the same syntax used by `Inventory`, e.g. `["Dash", "Keystone=4"]`
+    #[wasm_bindgen(getter_with_clone)]
+    pub items: Vec<String>,
+}
+impl From<&SeedgenInventory> for ItemSet {
+    fn from(inventory: &SeedgenInventory) -> ItemSet {
+        let mut items = inventory
+            .items
+            .iter()
+            .filter(|(_, amount)| **amount > 0)
+            .map(|(item, amount)| match amount {
+                1 => item.to_string(),
+                _ => format!("{item}={amount}"),
+            })
+            .collect::<Vec<_>>();
+        items.sort();
+        ItemSet { items }
+    }
+}
+
+const MAX_ALTERNATIVES: usize = 16;
+
+/// The items needed for a requirement, see `Graph.required_items`
+///
+/// World states are kept apart because the items they need depend on how they are reached
+enum Needed {
+    /// Alternative sets of items computed by seedgen
+    Items(Vec<SeedgenInventory>),
+    /// Needs the items to reach the world state with this node index
+    State(usize),
+    /// Needs all of the children
+    And(Vec<Needed>),
+    /// Needs any of the children
+    Or(Vec<Needed>),
+}
+impl Needed {
+    fn new(requirement: &SeedgenRequirement, player: &Player, states: &HashSet<usize>) -> Self {
+        let children = |requirements: &[SeedgenRequirement]| {
+            requirements
+                .iter()
+                .map(|requirement| Self::new(requirement, player, states))
+                .collect()
+        };
+        match requirement {
+            SeedgenRequirement::State(index) => Needed::State(*index),
+            SeedgenRequirement::And(requirements) if needs_state(requirement) => {
+                Needed::And(children(requirements))
+            }
+            SeedgenRequirement::Or(requirements) if needs_state(requirement) => {
+                Needed::Or(children(requirements))
+            }
+            _ => Needed::Items(minimize(
+                requirement
+                    .items_needed(player, states)
+                    .into_iter()
+                    .map(|(items, _)| items)
+                    .collect(),
+            )),
+        }
+    }
+    /// Calls `f` with the node index of every world state this needs
+    fn for_each_state(&self, f: &mut impl FnMut(usize)) {
+        match self {
+            Needed::Items(_) => {}
+            Needed::State(index) => f(*index),
+            Needed::And(children) | Needed::Or(children) => {
+                for child in children {
+                    child.for_each_state(f);
+                }
+            }
+        }
+    }
+    /// Returns the alternatives needed, using the alternatives found so far for world states
+    fn alternatives(
+        &self,
+        reached: &HashMap<usize, Vec<SeedgenInventory>>,
+    ) -> Vec<SeedgenInventory> {
+        match self {
+            Needed::Items(alternatives) => alternatives.clone(),
+            Needed::State(index) => reached.get(index).cloned().unwrap_or_default(),
+            Needed::And(children) => children
+                .iter()
+                .fold(vec![SeedgenInventory::default()], |alternatives, child| {
+                    combine(&alternatives, &child.alternatives(reached))
+                }),
+            Needed::Or(children) => minimize(
+                children
+                    .iter()
+                    .flat_map(|child| child.alternatives(reached))
+                    .collect(),
+            ),
+        }
+    }
+}
+/// Returns `true` if `requirement` contains a world state
+fn needs_state(requirement: &SeedgenRequirement) -> bool {
+    match requirement {
+        SeedgenRequirement::State(_) => true,
+        SeedgenRequirement::And(requirements) | SeedgenRequirement::Or(requirements) => {
+            requirements.iter().any(needs_state)
+        }
+        _ => false,
+    }
+}
+/// Returns the items in `items` beyond the amounts in `owned`
+fn without(items: &SeedgenInventory, owned: &SeedgenInventory) -> SeedgenInventory {
+    let mut items = items.clone();
+    for (item, amount) in &mut items.items {
+        *amount = amount.saturating_sub(owned.items.get(item).copied().unwrap_or_default());
+    }
+    items
+}
+/// Returns `true` if `items` has at least the amount of every item in `other`
+fn contains(items: &SeedgenInventory, other: &SeedgenInventory) -> bool {
+    other.items.iter().all(|(item, amount)| {
+        *amount == 0 || items.items.get(item).is_some_and(|owned| owned >= amount)
+    })
+}
+/// Returns the alternatives needed to meet both `a` and `b`
+fn combine(a: &[SeedgenInventory], b: &[SeedgenInventory]) -> Vec<SeedgenInventory> {
+    minimize(
+        a.iter()
+            .flat_map(|a| {
+                b.iter().map(|b| {
+                    let mut items = a.clone();
+                    for (item, amount) in &b.items {
+                        let entry = items.items.entry(item.clone()).or_default();
+                        *entry = (*entry).max(*amount);
+                    }
+                    items
+                })
+            })
+            .collect(),
+    )
+}
+/// Removes alternatives that contain another alternative and keeps at most `MAX_ALTERNATIVES` with the fewest items
+fn minimize(mut alternatives: Vec<SeedgenInventory>) -> Vec<SeedgenInventory> {
+    alternatives.sort_by_key(|items| {
+        (
+            items.items.values().filter(|amount| **amount > 0).count(),
+            items.items.values().sum::<u32>(),
+        )
+    });
+    let mut minimal: Vec<SeedgenInventory> = vec![];
+    for alternative in alternatives {
+        if !minimal
+            .iter()
+            .any(|smaller| contains(&alternative, smaller))
+        {
+            minimal.push(alternative);
+        }
+        if minimal.len() == MAX_ALTERNATIVES {
+            break;
+        }
+    }
+    minimal
+}
+
+#[cfg(test)]
+mod tests {
+    use wotw_seedgen::item::{Resource, Skill};
+
+    use super::*;
+
+    /// Edges as `(start, end, energy, health)`, usable if enough energy and health are left
+    fn search(edges: &[(usize, usize, f32, f32)], to: usize, energy: f32) -> Option<Vec<usize>> {
+        let start = Orbs {
+            health: 30.,
+            energy,
+        };
+        cheapest_path(0, to, start, |node, orbs| {
+            edges
+                .iter()
+                .filter(|(start, ..)| *start == node)
+                .filter(|(_, _, energy, health)| orbs.energy >= *energy && orbs.health > *health)
+                .map(|&(_, end, energy, health)| {
+                    let left = Orbs {
+                        health: orbs.health - health,
+                        energy: orbs.energy - energy,
+                    };
+                    (end, Cost { energy, health }, left, end)
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn path_is_cheapest() {
+        let edges = [(0, 1, 2., 0.), (1, 2, 0., 0.), (0, 2, 3., 0.)];
+        assert_eq!(search(&edges, 2, 3.), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn path_keeps_ways_with_more_orbs_left() {
+        // The cheaper way to 2 uses energy that is needed afterwards
+        let edges = [
+            (0, 2, 1., 0.),
+            (0, 1, 0., 20.),
+            (1, 2, 0., 0.),
+            (2, 3, 3., 0.),
+        ];
+        assert_eq!(search(&edges, 2, 3.), Some(vec![2]));
+        assert_eq!(search(&edges, 3, 3.), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn path_not_found() {
+        let edges = [(0, 1, 0., 0.), (1, 2, 4., 0.)];
+        assert_eq!(search(&edges, 2, 3.), None);
+        assert_eq!(search(&edges, 3, 3.), None);
+    }
+
+    fn items(items: &[(Item, u32)]) -> SeedgenInventory {
+        let mut inventory = SeedgenInventory::default();
+        inventory.items.extend(items.iter().cloned());
+        inventory
+    }
+    fn dash() -> Item {
+        Item::Skill(Skill::Dash)
+    }
+    fn bash() -> Item {
+        Item::Skill(Skill::Bash)
+    }
+    fn keystone() -> Item {
+        Item::Resource(Resource::Keystone)
+    }
+    fn assert_same(actual: &[SeedgenInventory], expected: &[SeedgenInventory]) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(contains(actual, expected) && contains(expected, actual));
+        }
+    }
+
+    #[test]
+    fn minimize_drops_supersets() {
+        let minimal = minimize(vec![
+            items(&[(dash(), 1), (bash(), 1)]),
+            items(&[(keystone(), 4)]),
+            items(&[(dash(), 1)]),
+            items(&[(keystone(), 2)]),
+            items(&[(dash(), 1)]),
+        ]);
+        assert_same(
+            &minimal,
+            &[items(&[(dash(), 1)]), items(&[(keystone(), 2)])],
+        );
+
+        let many = (0..20)
+            .map(|amount| items(&[(Item::SpiritLight(amount), 1)]))
+            .collect();
+        assert_eq!(minimize(many).len(), MAX_ALTERNATIVES);
+    }
+
+    #[test]
+    fn combine_keeps_highest_amounts() {
+        let combined = combine(
+            &[items(&[(dash(), 1)]), items(&[(keystone(), 2)])],
+            &[items(&[(keystone(), 4)])],
+        );
+        assert_same(&combined, &[items(&[(keystone(), 4)])]);
+
+        let combined = combine(
+            &[items(&[(dash(), 1)])],
+            &[items(&[(bash(), 1)]), items(&[(keystone(), 1)])],
+        );
+        assert_same(
+            &combined,
+            &[
+                items(&[(dash(), 1), (bash(), 1)]),
+                items(&[(dash(), 1), (keystone(), 1)]),
+            ],
+        );
+        assert!(combine(&[items(&[(dash(), 1)])], &[]).is_empty());
+    }
+}
@@ -0,0 +1,426 @@
+use std::fmt::{self, Display};
+
+use wasm_bindgen::prelude::*;
+
+use wasm_bindgen_helper_macros::*;
+
+use wotw_seedgen::settings::Difficulty;
+use wotw_seedgen::world::graph::{Node as SeedgenNode, Requirement as SeedgenRequirement};
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    /// The kind of a `Requirement`
+    pub enum RequirementKind {
+        /// Always met
+        Free,
+        /// Never met
+        Impossible,
+        /// A difficulty like `kii`, met if the logic difficulty is at least as high
+        ///
+        /// `normal` is met unless hard mode is enabled
+        Difficulty,
+        /// A trick like `SwordSentryJump`, met if the trick is enabled
+        Trick,
+        /// A skill like `Dash`, energy skills may have an `amount` of uses
+        Skill,
+        /// A spirit shard like `TripleJump`
+        Shard,
+        /// A resource like `Keystone` or `SpiritLight` with an `amount`
+        Resource,
+        /// A teleporter like `Marsh`
+        Teleporter,
+        /// Clean water
+        Water,
+        /// A world state like `MarshSpawn.Lever`, named after its `Node`
+        State,
+        /// Taking `amount` damage
+        Damage,
+        /// Having more than `amount` health, without losing it
+        Danger,
+        /// Defeating enemies, `name` lists them like `2xMantis+Slug`
+        Combat,
+        /// Defeating a boss with `amount` health
+        Boss,
+        /// Breaking a wall with `amount` health, `name` is `ShurikenBreak` or `SentryBreak` if a trick is used to break it
+        BreakWall,
+        /// Met if all `children` are met
+        And,
+        /// Met if any of the `children` is met
+        Or,
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __RequirementList {
+        inner: IntoIter<Requirement>,
+    }
+}
+
+#[wasm_bindgen]
+/// A requirement from the logic, structured as a tree
+#[derive(Clone)]
+pub struct Requirement {
+    pub(crate) kind: RequirementKind,
+    #[wasm_bindgen(getter_with_clone)]
+    /// The name of the skill, resource, etc.
+    ///
+    /// `undefined` for `Free`, `Impossible`, `And` and `Or`
+    pub name: Option<String>,
+    /// The amount needed, e.g. `20` for `Damage=20`
+    ///
+    /// `undefined` if no amount is given
+    pub amount: Option<f32>,
+    pub(crate) children: Vec<Requirement>,
+}
+#[wasm_bindgen]
+impl Requirement {
+    #[wasm_bindgen(getter)]
+    /// The `RequirementKind` of this `Requirement`
+    pub fn kind(&self) -> RequirementKindEnum {
+        self.kind.into_js_enum()
+    }
+    #[wasm_bindgen(getter)]
+    /// The nested requirements of `And` and `Or`, empty for other kinds
+    pub fn children(&self) -> RequirementArray {
+        __RequirementList::from(self.children.clone()).into_js_array()
+    }
+    #[wasm_bindgen(getter)]
+    /// A human-readable form of this `Requirement`, e.g. `Dash + Bash (kii)`
+    pub fn text(&self) -> String {
+        self.to_string()
+    }
+}
+impl Requirement {
+    fn leaf(kind: RequirementKind) -> Self {
+        Self {
+            kind,
+            name: None,
+            amount: None,
+            children: vec![],
+        }
+    }
+    fn group(kind: RequirementKind, children: Vec<Requirement>) -> Self {
+        let mut flattened = Vec::with_capacity(children.len());
+        for child in children {
+            if child.kind == kind {
+                flattened.extend(child.children);
+            } else {
+                flattened.push(child);
+            }
+        }
+        let (neutral, absorbing) = match kind {
+            RequirementKind::And => (RequirementKind::Free, RequirementKind::Impossible),
+            _ => (RequirementKind::Impossible, RequirementKind::Free),
+        };
+        if flattened.iter().any(|child| child.kind == absorbing) {
+            return Self::leaf(absorbing);
+        }
+        flattened.retain(|child| child.kind != neutral);
+
+        match flattened.len() {
+            0 => Self::leaf(neutral),
+            1 => flattened.pop().unwrap(),
+            _ => Self {
+                kind,
+                name: None,
+                amount: None,
+                children: flattened,
+            },
+        }
+    }
+    pub(crate) fn free() -> Self {
+        Self::leaf(RequirementKind::Free)
+    }
+    pub(crate) fn impossible() -> Self {
+        Self::leaf(RequirementKind::Impossible)
+    }
+    pub(crate) fn and(children: Vec<Requirement>) -> Self {
+        Self::group(RequirementKind::And, children)
+    }
+    pub(crate) fn or(children: Vec<Requirement>) -> Self {
+        Self::group(RequirementKind::Or, children)
+    }
+
+    fn named(kind: RequirementKind, name: impl Into<String>, amount: Option<f32>) -> Self {
+        Self {
+            kind,
+            name: Some(name.into()),
+            amount,
+            children: vec![],
+        }
+    }
+    /// Converts a requirement from seedgen's logic, where macros and regions are already expanded
+    ///
+    /// `nodes` are the nodes of the same logic, used to name world states
+    pub(crate) fn from_seedgen(requirement: &SeedgenRequirement, nodes: &[SeedgenNode]) -> Self {
+        let convert = |requirements: &[SeedgenRequirement]| {
+            requirements
+                .iter()
+                .map(|requirement| Self::from_seedgen(requirement, nodes))
+                .collect()
+        };
+        match requirement {
+            SeedgenRequirement::Free => Self::free(),
+            SeedgenRequirement::Impossible => Self::impossible(),
+            SeedgenRequirement::Difficulty(difficulty) => Self::named(
+                RequirementKind::Difficulty,
+                difficulty_name(*difficulty),
+                None,
+            ),
+            SeedgenRequirement::NormalGameDifficulty => {
+                Self::named(RequirementKind::Difficulty, "normal", None)
+            }
+            SeedgenRequirement::Trick(trick) => {
+                Self::named(RequirementKind::Trick, trick.to_string(), None)
+            }
+            SeedgenRequirement::Skill(skill)
+            | SeedgenRequirement::NonConsumingEnergySkill(skill) => {
+                Self::named(RequirementKind::Skill, skill.to_string(), None)
+            }
+            SeedgenRequirement::EnergySkill(skill, uses) => {
+                Self::named(RequirementKind::Skill, skill.to_string(), Some(*uses))
+            }
+            SeedgenRequirement::SpiritLight(amount) => Self::named(
+                RequirementKind::Resource,
+                "SpiritLight",
+                Some(*amount as f32),
+            ),
+            SeedgenRequirement::Resource(resource, amount) => Self::named(
+                RequirementKind::Resource,
+                resource.to_string(),
+                Some(*amount as f32),
+            ),
+            SeedgenRequirement::Shard(shard) => {
+                Self::named(RequirementKind::Shard, shard.to_string(), None)
+            }
+            SeedgenRequirement::Teleporter(teleporter) => {
+                Self::named(RequirementKind::Teleporter, teleporter.to_string(), None)
+            }
+            SeedgenRequirement::Water => Self::named(RequirementKind::Water, "Water", None),
+            SeedgenRequirement::State(index) => {
+                Self::named(RequirementKind::State, nodes[*index].identifier(), None)
+            }
+            SeedgenRequirement::Damage(amount) => {
+                Self::named(RequirementKind::Damage, "Damage", Some(*amount))
+            }
+            SeedgenRequirement::Danger(amount) => {
+                Self::named(RequirementKind::Danger, "Danger", Some(*amount))
+            }
+            SeedgenRequirement::Combat(enemies) => {
+                let enemies = enemies
+                    .iter()
+                    .map(|(enemy, amount)| match amount {
+                        1 => enemy.to_string(),
+                        _ => format!("{amount}x{enemy}"),
+                    })
+                    .collect::<Vec<_>>();
+                Self::named(RequirementKind::Combat, enemies.join("+"), None)
+            }
+            SeedgenRequirement::Boss(health) => {
+                Self::named(RequirementKind::Boss, "Boss", Some(*health))
+            }
+            SeedgenRequirement::BreakWall(health) => {
+                Self::named(RequirementKind::BreakWall, "BreakWall", Some(*health))
+            }
+            SeedgenRequirement::ShurikenBreak(health) => {
+                Self::named(RequirementKind::BreakWall, "ShurikenBreak", Some(*health))
+            }
+            SeedgenRequirement::SentryBreak(health) => {
+                Self::named(RequirementKind::BreakWall, "SentryBreak", Some(*health))
+            }
+            SeedgenRequirement::And(requirements) => Self::and(convert(requirements)),
+            SeedgenRequirement::Or(requirements) => Self::or(convert(requirements)),
+        }
+    }
+
+    /// The logic difficulty needed by a `Difficulty` requirement
+    ///
+    /// `None` for other kinds and `normal`
+    pub(crate) fn difficulty(&self) -> Option<Difficulty> {
+        if self.kind != RequirementKind::Difficulty {
+            return None;
+        }
+        match self.name.as_deref()? {
+            "moki" => Some(Difficulty::Moki),
+            "gorlek" => Some(Difficulty::Gorlek),
+            "kii" => Some(Difficulty::Kii),
+            "unsafe" => Some(Difficulty::Unsafe),
+            _ => None,
+        }
+    }
+    /// Returns `true` if meeting `self` always meets `other` as well, i.e. `self` is at least as strict as `other`
+    ///
+    /// This only compares the structure of both requirements, so it may return `false` for some requirements that do imply each other
+    pub(crate) fn implies(&self, other: &Requirement) -> bool {
+        match (self.kind, other.kind) {
+            (_, RequirementKind::Free) | (RequirementKind::Impossible, _) => true,
+            (RequirementKind::Or, _) => self.children.iter().all(|child| child.implies(other)),
+            (_, RequirementKind::And) => other.children.iter().all(|child| self.implies(child)),
+            (RequirementKind::And, _) => self.children.iter().any(|child| child.implies(other)),
+            (_, RequirementKind::Or) => other.children.iter().any(|child| self.implies(child)),
+            (RequirementKind::Difficulty, RequirementKind::Difficulty) => {
+                match (self.difficulty(), other.difficulty()) {
+                    (Some(difficulty), Some(other_difficulty)) => difficulty >= other_difficulty,
+                    _ => self.name == other.name,
+                }
+            }
+            (kind, other_kind) => {
+                kind == other_kind
+                    && self.name == other.name
+                    && self.amount.unwrap_or_default() >= other.amount.unwrap_or_default()
+            }
+        }
+    }
+}
+/// The name used for `difficulty` in `areas.wotw`
+fn difficulty_name(difficulty: Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Moki => "moki",
+        Difficulty::Gorlek => "gorlek",
+        Difficulty::Kii => "kii",
+        Difficulty::Unsafe => "unsafe",
+    }
+}
+impl Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            RequirementKind::Free => write!(f, "free"),
+            RequirementKind::Impossible => write!(f, "impossible"),
+            RequirementKind::Difficulty | RequirementKind::Trick => {
+                write!(f, "free ({})", self.name.as_deref().unwrap_or_default())
+            }
+            RequirementKind::And => {
+                let (qualifiers, requirements): (Vec<_>, Vec<_>) =
+                    self.children.iter().partition(|child| {
+                        matches!(
+                            child.kind,
+                            RequirementKind::Difficulty | RequirementKind::Trick
+                        )
+                    });
+                let requirements = requirements
+                    .iter()
+                    .map(|child| match child.kind {
+                        RequirementKind::Or => format!("({child})"),
+                        _ => child.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                let qualifiers = qualifiers
+                    .iter()
+                    .map(|child| child.name.as_deref().unwrap_or_default())
+                    .collect::<Vec<_>>();
+                match (requirements.is_empty(), qualifiers.is_empty()) {
+                    (true, _) => write!(f, "free ({})", qualifiers.join(", ")),
+                    (false, true) => write!(f, "{}", requirements.join(" + ")),
+                    (false, false) => write!(
+                        f,
+                        "{} ({})",
+                        requirements.join(" + "),
+                        qualifiers.join(", ")
+                    ),
+                }
+            }
+            RequirementKind::Or => {
+                let alternatives = self
+                    .children
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                write!(f, "{}", alternatives.join(" or "))
+            }
+            RequirementKind::Combat => {
+                write!(f, "Combat={}", self.name.as_deref().unwrap_or_default())
+            }
+            _ => {
+                let name = self.name.as_deref().unwrap_or_default();
+                match self.amount {
+                    Some(amount) => write!(f, "{name}={amount}"),
+                    None => write!(f, "{name}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(requirement: SeedgenRequirement) -> Requirement {
+        Requirement::from_seedgen(&requirement, &[])
+    }
+    fn damage(amount: f32) -> SeedgenRequirement {
+        SeedgenRequirement::Damage(amount)
+    }
+    fn difficulty(difficulty: Difficulty) -> SeedgenRequirement {
+        SeedgenRequirement::Difficulty(difficulty)
+    }
+
+    #[test]
+    fn from_seedgen_flattens_groups() {
+        let requirement = convert(SeedgenRequirement::And(vec![
+            SeedgenRequirement::Free,
+            SeedgenRequirement::And(vec![damage(20.), SeedgenRequirement::Boss(100.)]),
+            SeedgenRequirement::Or(vec![SeedgenRequirement::Impossible, damage(10.)]),
+        ]));
+        assert!(requirement.kind == RequirementKind::And);
+        assert_eq!(requirement.to_string(), "Damage=20 + Boss=100 + Damage=10");
+
+        let free = convert(SeedgenRequirement::Or(vec![
+            damage(20.),
+            SeedgenRequirement::Free,
+        ]));
+        assert!(free.kind == RequirementKind::Free);
+        let impossible = convert(SeedgenRequirement::And(vec![
+            damage(20.),
+            SeedgenRequirement::Impossible,
+        ]));
+        assert!(impossible.kind == RequirementKind::Impossible);
+    }
+
+    #[test]
+    fn display_separates_difficulties() {
+        let requirement = convert(SeedgenRequirement::And(vec![
+            difficulty(Difficulty::Kii),
+            SeedgenRequirement::Or(vec![damage(20.), SeedgenRequirement::BreakWall(12.)]),
+        ]));
+        assert_eq!(requirement.to_string(), "(Damage=20 or BreakWall=12) (kii)");
+        assert_eq!(
+            convert(difficulty(Difficulty::Gorlek)).to_string(),
+            "free (gorlek)"
+        );
+        assert_eq!(
+            convert(SeedgenRequirement::NormalGameDifficulty).to_string(),
+            "free (normal)"
+        );
+    }
+
+    #[test]
+    fn implies_compares_difficulties_and_amounts() {
+        let kii = convert(difficulty(Difficulty::Kii));
+        let gorlek = convert(difficulty(Difficulty::Gorlek));
+        assert!(kii.implies(&gorlek));
+        assert!(!gorlek.implies(&kii));
+
+        let more = convert(damage(30.));
+        let less = convert(damage(20.));
+        assert!(more.implies(&less));
+        assert!(!less.implies(&more));
+        assert!(!more.implies(&convert(SeedgenRequirement::Danger(20.))));
+
+        let both = convert(SeedgenRequirement::And(vec![
+            damage(20.),
+            SeedgenRequirement::Boss(100.),
+        ]));
+        let either = convert(SeedgenRequirement::Or(vec![
+            damage(20.),
+            SeedgenRequirement::Boss(100.),
+        ]));
+        assert!(both.implies(&either));
+        assert!(!either.implies(&both));
+        assert!(both.implies(&Requirement::free()));
+        assert!(Requirement::impossible().implies(&both));
+    }
+}
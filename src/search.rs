@@ -0,0 +1,419 @@
+use wasm_bindgen::prelude::*;
+
+use wotw_seedgen::files::FileAccess;
+use wotw_seedgen::header::Header;
+
+use wasm_bindgen_helper_macros::*;
+
+use crate::files::JsFileAccess;
+use crate::header_syntax;
+use crate::headers::{parse_annotations, parse_documentation};
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// The part of a header a `SearchMatch` was found in
+    pub enum SearchField {
+        /// The name the header is included by
+        Identifier,
+        /// The brief name from the header's documentation
+        Name,
+        /// The extended description from the header's documentation
+        Description,
+        /// The category from the header's annotations
+        Category,
+        /// The documentation of one of the header's parameters
+        Parameter,
+    }
+}
+impl SearchField {
+    fn weight(self) -> f32 {
+        match self {
+            SearchField::Identifier => 3.,
+            SearchField::Name => 4.,
+            SearchField::Description => 1.5,
+            SearchField::Category => 2.,
+            SearchField::Parameter => 1.,
+        }
+    }
+}
+
+#[wasm_bindgen]
+/// Search index over the documentation of a set of headers
+///
+/// Hidden headers are not indexed
+pub struct HeaderSearchIndex {
+    documents: Vec<Document>,
+}
+#[wasm_bindgen]
+impl HeaderSearchIndex {
+    #[wasm_bindgen(constructor)]
+    /// Creates a new `HeaderSearchIndex` over the headers with the given names
+    ///
+    /// The headers are read using the provided `file_access`
+    ///
+    /// @throws {string} if a header cannot be found or its annotations fail to parse
+    pub fn new(
+        names: Vec<String>,
+        file_access: &JsFileAccess,
+    ) -> Result<HeaderSearchIndex, String> {
+        let mut documents = vec![];
+        for name in names {
+            let header = file_access
+                .read_header(&name)
+                .map_err(|err| format!("failed to read header \"{name}\": {err}"))?;
+            let annotations = parse_annotations(&header)?;
+            if annotations.hide {
+                continue;
+            }
+            let documentation = parse_documentation(&header);
+
+            let mut fields = vec![Field::new(SearchField::Identifier, name.clone(), None)];
+            fields.extend(
+                documentation
+                    .name
+                    .map(|text| Field::new(SearchField::Name, text, None)),
+            );
+            fields.extend(
+                documentation
+                    .description
+                    .map(|text| Field::new(SearchField::Description, text, None)),
+            );
+            fields.extend(
+                annotations
+                    .category
+                    .map(|text| Field::new(SearchField::Category, text, None)),
+            );
+            fields.extend(
+                Header::parse_parameters(&header_syntax::seedgen_syntax(&header))
+                    .into_iter()
+                    .filter_map(|parameter| {
+                        let text = parameter.documentation?;
+                        Some(Field::new(
+                            SearchField::Parameter,
+                            text,
+                            Some(parameter.identifier),
+                        ))
+                    }),
+            );
+
+            documents.push(Document {
+                header: name,
+                fields,
+            });
+        }
+
+        Ok(Self { documents })
+    }
+
+    #[wasm_bindgen]
+    /// Returns the headers matching `query`, best matches first
+    ///
+    /// Every word of `query` is matched against the words of the indexed texts, tolerating small typos.
+    /// Headers matching more words of `query` are always ranked above headers matching fewer
+    pub fn search(&self, query: &str) -> SearchResultArray {
+        let terms = words(query)
+            .into_iter()
+            .map(|word| word.normalized)
+            .collect::<Vec<_>>();
+
+        let mut results = self
+            .documents
+            .iter()
+            .filter_map(|document| document.search(&terms))
+            .collect::<Vec<_>>();
+        results.sort_by(|(a_matched, a), (b_matched, b)| {
+            b_matched
+                .cmp(a_matched)
+                .then(b.score.total_cmp(&a.score))
+                .then_with(|| a.header.cmp(&b.header))
+        });
+
+        let results = results
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect::<Vec<_>>();
+        __SearchResultList::from(results).into_js_array()
+    }
+}
+
+struct Document {
+    header: String,
+    fields: Vec<Field>,
+}
+impl Document {
+    /// Returns the number of matched terms along with the `SearchResult`, or `None` if nothing matched
+    fn search(&self, terms: &[String]) -> Option<(usize, SearchResult)> {
+        let mut matched_terms = 0;
+        let mut score = 0.;
+        let mut matches = self
+            .fields
+            .iter()
+            .map(|field| SearchMatch {
+                kind: field.kind,
+                parameter: field.parameter.clone(),
+                text: field.text.clone(),
+                ranges: vec![],
+            })
+            .collect::<Vec<_>>();
+
+        for term in terms {
+            let mut best_score = 0.;
+            for (field, search_match) in self.fields.iter().zip(&mut matches) {
+                for word in &field.words {
+                    let word_score = match_score(term, &word.normalized);
+                    if word_score > 0. {
+                        best_score = f32::max(best_score, word_score * field.kind.weight());
+                        search_match.ranges.push(TextRange {
+                            start: word.start,
+                            end: word.end,
+                        });
+                    }
+                }
+            }
+            if best_score > 0. {
+                matched_terms += 1;
+                score += best_score;
+            }
+        }
+
+        if matched_terms == 0 {
+            return None;
+        }
+
+        let matches = matches
+            .into_iter()
+            .filter(|search_match| !search_match.ranges.is_empty())
+            .map(|mut search_match| {
+                search_match.ranges.sort_by_key(|range| range.start);
+                search_match.ranges.dedup_by_key(|range| range.start);
+                search_match
+            })
+            .collect();
+
+        Some((
+            matched_terms,
+            SearchResult {
+                header: self.header.clone(),
+                score,
+                matches,
+            },
+        ))
+    }
+}
+
+struct Field {
+    kind: SearchField,
+    parameter: Option<String>,
+    text: String,
+    words: Vec<Word>,
+}
+impl Field {
+    fn new(kind: SearchField, text: String, parameter: Option<String>) -> Self {
+        let words = words(&text);
+        Self {
+            kind,
+            parameter,
+            text,
+            words,
+        }
+    }
+}
+
+struct Word {
+    normalized: String,
+    start: usize,
+    end: usize,
+}
+
+/// Splits `text` into lowercase words, keeping their range in UTF-16 code units
+fn words(text: &str) -> Vec<Word> {
+    let mut words = vec![];
+    let mut current: Option<Word> = None;
+    let mut position = 0;
+    for char in text.chars() {
+        if char.is_alphanumeric() {
+            let word = current.get_or_insert_with(|| Word {
+                normalized: String::new(),
+                start: position,
+                end: position,
+            });
+            word.normalized.extend(char.to_lowercase());
+            word.end = position + char.len_utf16();
+        } else {
+            words.extend(current.take());
+        }
+        position += char.len_utf16();
+    }
+    words.extend(current);
+    words
+}
+
+/// Scores how well `word` matches the search `term`, `0` meaning it does not match
+fn match_score(term: &str, word: &str) -> f32 {
+    if word == term {
+        return 3.;
+    }
+    if word.starts_with(term) {
+        return 2.;
+    }
+
+    let term_length = term.chars().count();
+    let tolerance = match term_length {
+        0..=3 => return 0.,
+        4..=7 => 1,
+        _ => 2,
+    };
+    let word_prefix = word.chars().take(term_length).collect::<String>();
+    if edit_distance(term, word) <= tolerance || edit_distance(term, &word_prefix) <= tolerance {
+        1.
+    } else {
+        0.
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __SearchResultList {
+        inner: IntoIter<SearchResult>,
+    }
+}
+
+#[wasm_bindgen]
+/// A header matching a search query
+#[derive(Clone)]
+pub struct SearchResult {
+    #[wasm_bindgen(getter_with_clone)]
+    /// The name of the matching header
+    pub header: String,
+    /// How well the header matched, higher is better
+    pub score: f32,
+    matches: Vec<SearchMatch>,
+}
+#[wasm_bindgen]
+impl SearchResult {
+    #[wasm_bindgen(getter)]
+    /// The texts of the header that matched the search query
+    pub fn matches(&self) -> SearchMatchArray {
+        __SearchMatchList::from(self.matches.clone()).into_js_array()
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __SearchMatchList {
+        inner: IntoIter<SearchMatch>,
+    }
+}
+
+#[wasm_bindgen]
+/// A text of a header that matched a search query
+#[derive(Clone)]
+pub struct SearchMatch {
+    kind: SearchField,
+    #[wasm_bindgen(getter_with_clone)]
+    /// The identifier of the parameter whose documentation matched
+    ///
+    /// `undefined` unless the `field` is `Parameter`
+    pub parameter: Option<String>,
+    #[wasm_bindgen(getter_with_clone)]
+    /// The full text that matched
+    pub text: String,
+    ranges: Vec<TextRange>,
+}
+#[wasm_bindgen]
+impl SearchMatch {
+    #[wasm_bindgen(getter)]
+    /// The `SearchField` this text belongs to
+    pub fn field(&self) -> SearchFieldEnum {
+        self.kind.into_js_enum()
+    }
+    #[wasm_bindgen(getter)]
+    /// The ranges of `text` that matched, to be highlighted
+    pub fn ranges(&self) -> TextRangeArray {
+        __TextRangeList::from(self.ranges.clone()).into_js_array()
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __TextRangeList {
+        inner: IntoIter<TextRange>,
+    }
+}
+
+#[wasm_bindgen]
+/// A range inside a text
+///
+/// The indices count UTF-16 code units, matching JavaScript string indices
+#[derive(Clone, Copy)]
+pub struct TextRange {
+    /// Index of the first character in the range
+    pub start: usize,
+    /// Index after the last character in the range
+    pub end: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(text: &str) -> Vec<(String, usize, usize)> {
+        words(text)
+            .into_iter()
+            .map(|word| (word.normalized, word.start, word.end))
+            .collect()
+    }
+
+    #[test]
+    fn words_use_utf16_ranges() {
+        assert_eq!(
+            ranges("MarshSpawn.Main  Ore"),
+            [
+                ("marshspawn".to_owned(), 0, 10),
+                ("main".to_owned(), 11, 15),
+                ("ore".to_owned(), 17, 20),
+            ]
+        );
+        // The emoji takes two UTF-16 code units, "é" only one
+        assert_eq!(ranges("🌊 Éclair"), [("éclair".to_owned(), 3, 9)]);
+        assert_eq!(ranges(" .,"), []);
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("dash", "dash"), 0);
+        assert_eq!(edit_distance("ü", "u"), 1);
+    }
+
+    #[test]
+    fn match_scores() {
+        assert_eq!(match_score("dash", "dash"), 3.);
+        assert_eq!(match_score("da", "dash"), 2.);
+        // Short terms have to match exactly or as a prefix
+        assert_eq!(match_score("dsh", "dash"), 0.);
+        assert_eq!(match_score("bush", "bash"), 1.);
+        assert_eq!(match_score("bsah", "bash"), 0.);
+        assert_eq!(match_score("grapple", "grappling"), 1.);
+        assert_eq!(match_score("launch", "marsh"), 0.);
+    }
+}
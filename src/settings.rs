@@ -11,7 +11,7 @@ use crate::files::JsFileAccess;
 ///
 /// Using the same settings will result in generating the same seed (unless the used header files change)
 #[wasm_bindgen]
-pub struct UniverseSettings(SeedgenUniverseSettings);
+pub struct UniverseSettings(pub(crate) SeedgenUniverseSettings);
 #[wasm_bindgen]
 impl UniverseSettings {
     /// Returns the default `UniverseSettings`
@@ -65,7 +65,7 @@ impl UniverseSettings {
 ///
 /// See the [Multiplayer wiki page](https://wiki.orirando.com/features/multiplayer) for an explanation of worlds
 #[wasm_bindgen]
-pub struct WorldSettings(SeedgenWorldSettings);
+pub struct WorldSettings(pub(crate) SeedgenWorldSettings);
 #[wasm_bindgen]
 impl WorldSettings {
     /// Returns the default Settings
@@ -0,0 +1,247 @@
+use std::collections::{BTreeSet, HashMap};
+
+use wasm_bindgen::prelude::*;
+
+use crate::map::{__ConnectionList, Connection, ConnectionArray, Graph, Node, Vector2};
+
+/// Side length of the grid cells used by the `SpatialIndex`, using in-game units
+const CELL_SIZE: f32 = 50.;
+
+/// A uniform grid over the `Node`s and `Connection`s of a `Graph` to quickly find them by position
+pub(crate) struct SpatialIndex {
+    /// Indices into the `Graph`'s `nodes` by cell
+    nodes: HashMap<(i32, i32), Vec<usize>>,
+    /// Indices into the `Graph`'s `connections` by every cell their line passes through
+    connections: HashMap<(i32, i32), Vec<usize>>,
+    /// The positions of both ends of each of the `Graph`'s `connections`, `None` if either end has no `Node`
+    segments: Vec<Option<(Vector2, Vector2)>>,
+}
+impl SpatialIndex {
+    pub fn new(nodes: &[Node], connections: &[Connection]) -> Self {
+        let mut index = Self {
+            nodes: HashMap::new(),
+            connections: HashMap::new(),
+            segments: Vec::with_capacity(connections.len()),
+        };
+        for (node_index, node) in nodes.iter().enumerate() {
+            index
+                .nodes
+                .entry(cell(node.position))
+                .or_default()
+                .push(node_index);
+        }
+        let positions = node_positions(nodes);
+        for (connection_index, connection) in connections.iter().enumerate() {
+            let segment = positions
+                .get(connection.start.as_str())
+                .zip(positions.get(connection.end.as_str()))
+                .map(|(start, end)| (*start, *end));
+            index.segments.push(segment);
+            let Some((start, end)) = segment else {
+                continue;
+            };
+            for (x, y) in cells_along(start, end) {
+                index
+                    .connections
+                    .entry((x, y))
+                    .or_default()
+                    .push(connection_index);
+            }
+        }
+        index
+    }
+
+    /// Returns the entries of all cells overlapping the rectangle between `min` and `max`, without duplicates
+    fn query(
+        cells: &HashMap<(i32, i32), Vec<usize>>,
+        min: Vector2,
+        max: Vector2,
+    ) -> BTreeSet<usize> {
+        let (min_cell, max_cell) = (cell(min), cell(max));
+        let area = (i64::from(max_cell.0) - i64::from(min_cell.0) + 1)
+            * (i64::from(max_cell.1) - i64::from(min_cell.1) + 1);
+        // For large areas, checking every occupied cell is cheaper than checking every cell in the area
+        if area > cells.len() as i64 {
+            cells
+                .iter()
+                .filter(|((x, y), _)| {
+                    (min_cell.0..=max_cell.0).contains(x) && (min_cell.1..=max_cell.1).contains(y)
+                })
+                .flat_map(|(_, entries)| entries.iter().copied())
+                .collect()
+        } else {
+            cells_between(min, max)
+                .filter_map(|cell| cells.get(&cell))
+                .flatten()
+                .copied()
+                .collect()
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Vector2 {
+    /// Creates a new `Vector2`
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: f32, y: f32) -> Vector2 {
+        Vector2 { x, y }
+    }
+}
+
+#[wasm_bindgen]
+impl Graph {
+    #[wasm_bindgen]
+    /// Returns the names of all `Node`s inside the rectangle between `min` and `max`, using in-game coordinates
+    pub fn nodes_in_rect(&self, min: &Vector2, max: &Vector2) -> Vec<String> {
+        let (min, max) = bounds(*min, *max, 0.);
+        SpatialIndex::query(&self.spatial_index.nodes, min, max)
+            .into_iter()
+            .map(|index| &self.nodes[index])
+            .filter(|node| {
+                (min.x..=max.x).contains(&node.position.x)
+                    && (min.y..=max.y).contains(&node.position.y)
+            })
+            .map(|node| node.name.clone())
+            .collect()
+    }
+    #[wasm_bindgen]
+    /// Returns the name of the `Node` closest to `point`, using in-game coordinates
+    ///
+    /// `undefined` if no `Node` is within `max_distance`
+    pub fn nearest_node(&self, point: &Vector2, max_distance: f32) -> Option<String> {
+        let (min, max) = bounds(*point, *point, max_distance);
+        SpatialIndex::query(&self.spatial_index.nodes, min, max)
+            .into_iter()
+            .map(|index| {
+                let node = &self.nodes[index];
+                (node, distance(*point, node.position))
+            })
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(node, _)| node.name.clone())
+    }
+    #[wasm_bindgen]
+    /// Returns all `Connection`s passing within `tolerance` of `point`, using in-game coordinates
+    ///
+    /// The distance is measured to the straight line between both ends of the `Connection`, closest `Connection`s come first
+    pub fn connections_near(&self, point: &Vector2, tolerance: f32) -> ConnectionArray {
+        let (min, max) = bounds(*point, *point, tolerance);
+        let mut connections = SpatialIndex::query(&self.spatial_index.connections, min, max)
+            .into_iter()
+            .filter_map(|index| {
+                let (start, end) = self.spatial_index.segments[index]?;
+                let distance = segment_distance(*point, start, end);
+                (distance <= tolerance).then_some((&self.connections[index], distance))
+            })
+            .collect::<Vec<_>>();
+        connections.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        let connections = connections
+            .into_iter()
+            .map(|(connection, _)| connection.clone())
+            .collect::<Vec<_>>();
+        __ConnectionList::from(connections).into_js_array()
+    }
+}
+
+fn node_positions(nodes: &[Node]) -> HashMap<&str, Vector2> {
+    nodes
+        .iter()
+        .map(|node| (node.name.as_str(), node.position))
+        .collect()
+}
+fn cell(position: Vector2) -> (i32, i32) {
+    (
+        (position.x / CELL_SIZE).floor() as i32,
+        (position.y / CELL_SIZE).floor() as i32,
+    )
+}
+fn cells_between(min: Vector2, max: Vector2) -> impl Iterator<Item = (i32, i32)> {
+    let (min, max) = (cell(min), cell(max));
+    (min.0..=max.0).flat_map(move |x| (min.1..=max.1).map(move |y| (x, y)))
+}
+/// Returns the cells the line segment between `start` and `end` passes through, in order
+///
+/// Queries grow their rectangle by the tolerance, so the segment doesn't need any padding here
+fn cells_along(start: Vector2, end: Vector2) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = cell(start);
+    let last = cell(end);
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    // The fraction of the segment at which it crosses the next cell border on an axis, and how much further the border after that is
+    let borders = |position: f32, cell: i32, delta: f32| {
+        if delta == 0. {
+            return (f32::INFINITY, f32::INFINITY);
+        }
+        let border = if delta > 0. { cell + 1 } else { cell } as f32 * CELL_SIZE;
+        ((border - position) / delta, CELL_SIZE / delta.abs())
+    };
+    let (mut next_x, step_x) = borders(start.x, x, dx);
+    let (mut next_y, step_y) = borders(start.y, y, dy);
+
+    let mut cells = vec![(x, y)];
+    // Counting the steps instead of comparing floats guarantees ending in the last cell
+    for _ in 0..(last.0 - x).abs() + (last.1 - y).abs() {
+        if y == last.1 || (x != last.0 && next_x < next_y) {
+            x += (last.0 - x).signum();
+            next_x += step_x;
+        } else {
+            y += (last.1 - y).signum();
+            next_y += step_y;
+        }
+        cells.push((x, y));
+    }
+    cells
+}
+/// Returns the corners of the rectangle containing `a` and `b`, grown by `margin` on every side
+fn bounds(a: Vector2, b: Vector2, margin: f32) -> (Vector2, Vector2) {
+    (
+        Vector2 {
+            x: a.x.min(b.x) - margin,
+            y: a.y.min(b.y) - margin,
+        },
+        Vector2 {
+            x: a.x.max(b.x) + margin,
+            y: a.y.max(b.y) + margin,
+        },
+    )
+}
+fn distance(a: Vector2, b: Vector2) -> f32 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+/// Returns the distance from `point` to the line segment between `start` and `end`
+fn segment_distance(point: Vector2, start: Vector2, end: Vector2) -> f32 {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0. {
+        return distance(point, start);
+    }
+    let t = (((point.x - start.x) * dx + (point.y - start.y) * dy) / length_squared).clamp(0., 1.);
+    let closest = Vector2 {
+        x: start.x + t * dx,
+        y: start.y + t * dy,
+    };
+    distance(point, closest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(start: (f32, f32), end: (f32, f32)) -> Vec<(i32, i32)> {
+        cells_along(Vector2::new(start.0, start.1), Vector2::new(end.0, end.1))
+    }
+
+    #[test]
+    fn segment_cells() {
+        assert_eq!(cells((10., 10.), (20., 20.)), [(0, 0)]);
+        assert_eq!(
+            cells((10., 10.), (160., 10.)),
+            [(0, 0), (1, 0), (2, 0), (3, 0)]
+        );
+        assert_eq!(cells((10., 10.), (-10., -30.)), [(0, 0), (0, -1), (-1, -1)]);
+        // A diagonal only passes through a thin band of its bounding box
+        let diagonal = cells((10., 10.), (190., 140.));
+        assert_eq!(diagonal.first(), Some(&(0, 0)));
+        assert_eq!(diagonal.last(), Some(&(3, 2)));
+        assert_eq!(diagonal.len(), 6);
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use wasm_bindgen_helper_macros::*;
+
+use crate::header_syntax;
+
+ts_enum! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy)]
+    /// How a header uses an uber state
+    pub enum UberStateAccess {
+        /// The header checks the value of the uber state, e.g. as a pickup trigger or in a condition
+        Read,
+        /// The header changes the value of the uber state, e.g. through an uber state item or a timer
+        Write,
+    }
+}
+
+/// The columns `state_data.csv` starts with, any further columns are ignored
+const STATE_DATA_COLUMNS: [&str; 4] = ["UberGroupName", "UberGroup", "UberIdName", "UberId"];
+const GROUP_NAME_COLUMN: usize = 0;
+const GROUP_ID_COLUMN: usize = 1;
+const STATE_NAME_COLUMN: usize = 2;
+const STATE_ID_COLUMN: usize = 3;
+
+/// Human-readable names of the uber states in `state_data.csv`, by `group|id`
+pub(crate) struct StateData {
+    names: HashMap<(i32, i32), String>,
+}
+impl StateData {
+    /// Parses csv data as usually used by `state_data.csv`
+    ///
+    /// The first line has to name the columns as in `STATE_DATA_COLUMNS`
+    pub fn parse(state_data: &str) -> Result<Self, String> {
+        let mut lines = state_data.lines().filter(|line| !line.trim().is_empty());
+        let columns = lines
+            .next()
+            .ok_or_else(|| "state data is empty".to_string())?
+            .split(',')
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        if !columns.starts_with(&STATE_DATA_COLUMNS) {
+            return Err(format!(
+                "expected the state data to start with the columns {}, found {}",
+                STATE_DATA_COLUMNS.join(", "),
+                columns.join(", ")
+            ));
+        }
+
+        let mut names = HashMap::new();
+        for (index, line) in lines.enumerate() {
+            let values = line.split(',').map(str::trim).collect::<Vec<_>>();
+            let value = |column: usize| {
+                values.get(column).copied().ok_or_else(|| {
+                    format!("line {} of the state data is missing columns", index + 2)
+                })
+            };
+            let parse_id = |column: usize| {
+                let id = value(column)?;
+                id.parse::<i32>().map_err(|_| {
+                    format!(
+                        "line {} of the state data has an invalid id \"{id}\"",
+                        index + 2
+                    )
+                })
+            };
+            let name = format!(
+                "{}.{}",
+                value(GROUP_NAME_COLUMN)?,
+                value(STATE_NAME_COLUMN)?
+            );
+            names.insert(
+                (parse_id(GROUP_ID_COLUMN)?, parse_id(STATE_ID_COLUMN)?),
+                name,
+            );
+        }
+
+        Ok(Self { names })
+    }
+    /// Returns the name of the uber state as `GroupName.StateName`
+    pub fn name(&self, group: i32, id: i32) -> Option<&str> {
+        self.names.get(&(group, id)).map(String::as_str)
+    }
+}
+
+wrapper_list! {
+    #[wasm_bindgen]
+    #[derive(Clone)]
+    pub struct __UberStateUsageList {
+        inner: IntoIter<UberStateUsage>,
+    }
+}
+
+/// A reference to an uber state inside a header
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct UberStateUsage {
+    /// The uber group id
+    pub group: i32,
+    /// The uber state id
+    pub id: i32,
+    /// The name of the uber state as `GroupName.StateName`
+    ///
+    /// `undefined` if the uber state does not exist in the state data
+    #[wasm_bindgen(getter_with_clone)]
+    pub name: Option<String>,
+    /// `false` if the uber state does not exist in the state data, which likely means it contains a typo
+    ///
+    /// Uber states in group 3 are seedgen events like spawn and never considered unknown
+    pub known: bool,
+    /// Zero-based index of the line containing the reference
+    pub line: usize,
+    /// Column of the first character of `group|id`, in UTF-16 code units
+    pub start: usize,
+    /// Column after the last character of `group|id`, in UTF-16 code units
+    pub end: usize,
+    access: UberStateAccess,
+}
+#[wasm_bindgen]
+impl UberStateUsage {
+    /// Whether the header reads or writes this uber state
+    #[wasm_bindgen(getter)]
+    pub fn access(&self) -> UberStateAccessEnum {
+        self.access.into_js_enum()
+    }
+}
+
+/// Returns every uber state a given header syntax reads or writes, resolved using `state_data`
+///
+/// `state_data` should provide csv data as usually used by `state_data.csv`
+///
+/// The following references are recognized:
+/// - pickup triggers like `6|1` or `6|1>2` read the uber state
+/// - `$(group|id)` references inside text read the uber state
+/// - uber state items (`8|group|id|type|value`) write the uber state
+/// - `StopEqual`, `StopGreater` and `StopLess` commands (`4|4|group|id|value` through `4|6|...`) read the uber state
+/// - `IfEqual`, `IfGreater` and `IfLess` commands (`4|17|group|id|value|item` through `4|19|...`) read the uber state, their nested item is checked as well
+/// - `StartTimer` and `StopTimer` commands (`4|9|group|id` and `4|10|...`) write the uber state
+///
+/// Items are checked both after pickup triggers and in `!!add` and `!!remove` commands
+///
+/// @throws {string} if `state_data` fails to parse
+#[wasm_bindgen]
+pub fn uber_state_usage(header: &str, state_data: &str) -> Result<UberStateUsageArray, String> {
+    let state_data = StateData::parse(state_data)?;
+
+    let usages = header_syntax::uber_state_spans(header)
+        .into_iter()
+        .filter_map(|usage| {
+            let group = usage.group.parse().ok()?;
+            let id = usage.id.parse().ok()?;
+            let name = state_data.name(group, id).map(str::to_owned);
+            let access = if usage.write {
+                UberStateAccess::Write
+            } else {
+                UberStateAccess::Read
+            };
+            Some(UberStateUsage {
+                group,
+                id,
+                known: name.is_some() || group == 3,
+                name,
+                line: usage.span.line,
+                start: usage.span.start,
+                end: usage.span.end,
+                access,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(__UberStateUsageList::from(usages).into_js_array())
+}